@@ -0,0 +1,122 @@
+use anchor_lang::{prelude::*, solana_program::system_instruction};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+declare_id!("5WpHV4FkptvRQzztYW4aJzqofbJn2zh1aUxqMGSyBCFm");
+
+#[program]
+pub mod mock_amm {
+    use super::*;
+
+    //Seeds a constant product pool, the SOL leg is held by the pool account itself
+    pub fn initialize_pool(
+        ctx: Context<InitializePool>,
+        sol_amount: u64,
+        token_amount: u64,
+    ) -> Result<()> {
+        require!(sol_amount > 0 && token_amount > 0, AmmError::EmptyLiquidity);
+
+        //Deposit SOL
+        let transfer_instruction = system_instruction::transfer(
+            ctx.accounts.sol_source.key,
+            &ctx.accounts.pool.key(),
+            sol_amount,
+        );
+        anchor_lang::solana_program::program::invoke(
+            &transfer_instruction,
+            &[
+                ctx.accounts.sol_source.to_account_info(),
+                ctx.accounts.pool.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+
+        //Deposit Tokens
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.token_source.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.pool_token_account.to_account_info(),
+                    authority: ctx.accounts.token_source_authority.to_account_info(),
+                },
+            ),
+            token_amount,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        let pool = &mut ctx.accounts.pool;
+        pool.mint = ctx.accounts.mint.key();
+        pool.sol_reserves = sol_amount;
+        pool.token_reserves = token_amount;
+        pool.bump = ctx.bumps.pool;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    #[account(mut)]
+    payer: Signer<'info>,
+
+    #[account(mut)]
+    sol_source: Signer<'info>,
+
+    token_source_authority: Signer<'info>,
+
+    mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Pool::INIT_SPACE,
+        seeds = [Pool::SEED_PREFIX.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
+    pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program,
+    )]
+    pool_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = token_source_authority,
+        token::token_program = token_program,
+    )]
+    token_source: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    system_program: Program<'info, System>,
+
+    token_program: Interface<'info, TokenInterface>,
+
+    associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    pub mint: Pubkey,
+    pub sol_reserves: u64,
+    pub token_reserves: u64,
+    pub bump: u8,
+}
+
+impl Pool {
+    pub const SEED_PREFIX: &'static str = "pool";
+}
+
+#[error_code]
+pub enum AmmError {
+    #[msg("Pool liquidity is 0")]
+    EmptyLiquidity,
+}
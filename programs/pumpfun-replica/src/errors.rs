@@ -8,6 +8,7 @@ pub enum ContractError {
     #[msg("Bonding Curve Complete")]
     BondingCurveComplete,
 
+    //no longer raised since fees go to the fee vault, kept so later error codes keep their numbers
     #[msg("Invalid Fee Receiver")]
     InvalidFeeReceiver,
 
@@ -30,5 +31,85 @@ pub enum ContractError {
     InvalidMintDecimals,
 
     #[msg("Wrong Authority")]
-    WrongAuthority
+    WrongAuthority,
+
+    #[msg("Slippage: Too Little Tokens Received")]
+    TooLittleTokensReceived,
+
+    #[msg("Slippage: Too Little SOL Received")]
+    TooLittleSolReceived,
+
+    #[msg("Slippage: Too Much SOL Required")]
+    TooMuchSolRequired,
+
+    #[msg("Slippage: Too Many Tokens Required")]
+    TooManyTokensRequired,
+
+    #[msg("Invalid Global Settings")]
+    InvalidGlobalSettings,
+
+    //no longer raised since the fee never exceeds the sale proceeds,
+    //kept so later error codes keep their numbers
+    #[msg("Sell proceeds do not cover the fee")]
+    SellBelowFee,
+
+    #[msg("Bonding Curve Not Complete")]
+    BondingCurveNotComplete,
+
+    #[msg("Bonding Curve Already Migrated")]
+    BondingCurveMigrated,
+
+    #[msg("Invalid LP Config")]
+    InvalidLpConfig,
+
+    #[msg("Insufficient Tokens For Migration")]
+    InsufficientMigrationTokens,
+
+    #[msg("Freeze Authority Cannot Be Revoked")]
+    InvalidFreezeAuthority,
+
+    #[msg("Unsupported Mint Extension")]
+    UnsupportedMintExtension,
+
+    #[msg("Metadata Accounts Missing")]
+    MissingMetadataAccounts,
+
+    #[msg("Withdraw amount is 0")]
+    MinWithdraw,
+
+    #[msg("Insufficient Fees In Vault")]
+    InsufficientVaultFees,
+
+    #[msg("Invalid Referrer")]
+    InvalidReferrer,
+
+    #[msg("Referrer Cannot Be The User")]
+    ReferrerIsUser,
+
+    #[msg("Trading Is Paused")]
+    GlobalPaused,
+
+    #[msg("Bonding Curve Halted")]
+    BondingCurveHalted,
+
+    #[msg("Launch Parameters Out Of Bounds")]
+    LaunchParamsOutOfBounds,
+
+    #[msg("Invalid Launch Parameters")]
+    InvalidLaunchParams,
+
+    #[msg("Buy Exceeds Launch Window Cap")]
+    LaunchWindowCapExceeded,
+
+    #[msg("Metadata Pointer Must Target The Mint")]
+    InvalidMetadataPointer,
+
+    #[msg("Launch Buys Account Required During Launch Window")]
+    LaunchBuysRequired,
+
+    #[msg("Launch Window Closed")]
+    LaunchWindowClosed,
+
+    #[msg("Launch Window Still Open")]
+    LaunchWindowOpen,
 }
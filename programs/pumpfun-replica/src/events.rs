@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct CreateEvent {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub creator: Pubkey,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub real_token_reserves: u64,
+    pub token_total_supply: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TradeEvent {
+    pub mint: Pubkey,
+    pub user: Pubkey,
+    pub is_buy: bool,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub fee: u64,
+    pub creator_fee: u64,
+    pub referral_fee: u64,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MigrateEvent {
+    pub mint: Pubkey,
+    pub pool: Pubkey,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub fee: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CompleteEvent {
+    pub mint: Pubkey,
+    pub user: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WithdrawFeesEvent {
+    pub authority: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub total_fees_collected: u64,
+    pub total_fees_withdrawn: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ClaimCreatorFeesEvent {
+    pub creator: Pubkey,
+    pub amount: u64,
+    pub total_fees_accrued: u64,
+    pub total_fees_claimed: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,23 @@
+use crate::errors::*;
+use crate::states::global::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    new_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Global::SEED_PREFIX.as_bytes()],
+        constraint = global.initialized @ ContractError::NotInitialized,
+        bump,
+    )]
+    global: Box<Account<'info, Global>>,
+}
+
+impl AcceptAuthority<'_> {
+    pub fn handler(ctx: Context<AcceptAuthority>, role: AuthorityRole) -> Result<()> {
+        let new_authority = ctx.accounts.new_authority.key();
+        ctx.accounts.global.accept_authority(role, new_authority)
+    }
+}
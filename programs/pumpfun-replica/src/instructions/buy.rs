@@ -1,13 +1,14 @@
 use crate::errors::*;
-use crate::states::{bonding_curve::*, global::*};
-use anchor_lang::{
-    prelude::*,
-    solana_program::{program::invoke_signed, system_instruction},
+use crate::events::*;
+use crate::states::{
+    bonding_curve::*, creator_vault::*, fee_vault::*, global::*, launch_buys::*, referrer_stats::*,
 };
+use crate::utils::fees::*;
+use anchor_lang::{prelude::*, solana_program::system_instruction};
 
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{self, Mint, Token, TokenAccount, Transfer},
+    token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
 #[derive(Accounts)]
@@ -17,31 +18,56 @@ pub struct Buy<'info> {
 
     #[account(
         seeds = [Global::SEED_PREFIX.as_bytes()],
-        constraint = global.initialized == true @ ContractError::NotInitialized,
+        constraint = global.initialized @ ContractError::NotInitialized,
+        constraint = !global.paused @ ContractError::GlobalPaused,
         bump,
     )]
-    global: Box<Account<'info, Global>>,
+    pub global: Box<Account<'info, Global>>,
 
+    #[account(
+        mut,
+        seeds = [FeeVault::SEED_PREFIX.as_bytes()],
+        bump = fee_vault.bump,
+    )]
+    fee_vault: Box<Account<'info, FeeVault>>,
+
+    #[account(
+        mut,
+        seeds = [CreatorVault::SEED_PREFIX.as_bytes(), bonding_curve.creator.as_ref()],
+        bump = creator_vault.bump,
+    )]
+    creator_vault: Box<Account<'info, CreatorVault>>,
+
+    //Optional referrer routing the trade, paid a share of the protocol fee
     #[account(mut)]
-    ///CHECK: Receiver for FEES
-    fee_receiver: AccountInfo<'info>,
+    ///CHECK: Wallet registered as a referrer, matched against referrer_stats
+    referrer: Option<AccountInfo<'info>>,
+
+    #[account(
+        mut,
+        seeds = [ReferrerStats::SEED_PREFIX.as_bytes(), referrer_stats.referrer.as_ref()],
+        bump = referrer_stats.bump,
+    )]
+    referrer_stats: Option<Box<Account<'info, ReferrerStats>>>,
 
-    mint: Box<Account<'info, Mint>>,
+    mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
         seeds = [BondingCurve::SEED_PREFIX.as_bytes(), mint.to_account_info().key.as_ref()],
-        constraint = bonding_curve.complete == false @ ContractError::BondingCurveComplete,
+        constraint = !bonding_curve.complete @ ContractError::BondingCurveComplete,
+        constraint = !bonding_curve.halted @ ContractError::BondingCurveHalted,
         bump,
     )]
-    bonding_curve: Box<Account<'info, BondingCurve>>,
+    pub bonding_curve: Box<Account<'info, BondingCurve>>,
 
     #[account(
         mut,
         associated_token::mint = mint,
         associated_token::authority = bonding_curve,
+        associated_token::token_program = token_program,
     )]
-    bonding_curve_token_account: Box<Account<'info, TokenAccount>>,
+    bonding_curve_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
@@ -55,12 +81,21 @@ pub struct Buy<'info> {
         mut,
         associated_token::mint = mint,
         associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    user_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    //Per wallet tally of buys on this curve, only needed during the launch window
+    #[account(
+        mut,
+        seeds = [LaunchBuys::SEED_PREFIX.as_bytes(), bonding_curve.key().as_ref(), user.key().as_ref()],
+        bump = launch_buys.bump,
     )]
-    user_token_account: Box<Account<'info, TokenAccount>>,
+    launch_buys: Option<Box<Account<'info, LaunchBuys>>>,
 
     system_program: Program<'info, System>,
 
-    token_program: Program<'info, Token>,
+    token_program: Interface<'info, TokenInterface>,
 
     associated_token_program: Program<'info, AssociatedToken>,
 }
@@ -69,109 +104,154 @@ impl Buy<'_> {
     pub fn validate(&self, amount: u64) -> Result<()> {
         require!(amount > 0, ContractError::MinBuy);
 
+        validate_referrer(
+            &self.user.key(),
+            self.referrer.as_ref(),
+            self.referrer_stats.as_deref(),
+        )?;
+
+        Ok(())
+    }
+
+    pub fn handler(ctx: Context<Buy>, sol_amount: u64, min_tokens_out: u64) -> Result<()> {
+        //validate
+        ctx.accounts.validate(sol_amount)?;
+        msg!("input sol amount: {}", sol_amount);
+
+        //calculate tokens to be bought, same path as quote_buy
+        let quote = ctx
+            .accounts
+            .bonding_curve
+            .quote_buy(&ctx.accounts.global, sol_amount)
+            .ok_or(ContractError::CalculationError)?;
+        msg!("this is the token amount: {}", quote.token_amount);
+
+        //Slippage Check
         require!(
-            self.fee_receiver.key() == self.global.fee_receiver,
-            ContractError::InvalidFeeReceiver
+            quote.token_amount >= min_tokens_out,
+            ContractError::TooLittleTokensReceived
         );
 
+        ctx.accounts.execute_buy(&quote, ctx.bumps.bonding_curve)
+    }
+
+    //Settles a quoted buy, shared by buy and buy_exact_tokens: the user pays the
+    //curve leg into escrow plus the fee, and receives the quoted tokens
+    pub fn execute_buy(&mut self, quote: &TradeQuote, bonding_curve_bump: u8) -> Result<()> {
         let rent = Rent::get()?;
         let min_rent = rent.minimum_balance(0); // 0 for data size since this is just a native SOL account
         require!(
-            self.user.get_lamports() >= amount.checked_add(min_rent).unwrap(),
+            self.user.get_lamports()
+                >= quote
+                    .sol_amount
+                    .checked_add(quote.fee)
+                    .and_then(|amount| amount.checked_add(min_rent))
+                    .ok_or(ContractError::CalculationError)?,
             ContractError::InsufficientUserSOL,
         );
 
-        Ok(())
-    }
-    pub fn handler(ctx: Context<Buy>, sol_amount: u64) -> Result<()> {
-        //validate
-        ctx.accounts.validate(sol_amount)?;
-        msg!("input sol amount: {}", sol_amount);
-
-        let bonding_curve = &mut ctx.accounts.bonding_curve;
-        //calculate tokens to be bought
-        let mut token_amount = bonding_curve
-            .get_tokens_for_buy_with_sol(sol_amount)
-            .ok_or(ContractError::CalculationError)?;
-        msg!("this is the token amount: {}", token_amount);
-
-        let mut last_buy = false;
-        let mut calc_sol_amount = sol_amount;
-        let fee_lamports = 1_000_000;
-
-        if token_amount >= bonding_curve.real_token_reserves {
-            last_buy = true;
-            //set token to existing valuvaluee in reserve and compute new sol_amount to be paid
-            token_amount = bonding_curve.real_token_reserves;
-            calc_sol_amount = bonding_curve
-                .recompute_sol_amount_for_last_buy()
+        //wallets are capped while the curve is in its launch window
+        if self
+            .bonding_curve
+            .in_launch_window(Clock::get()?.unix_timestamp)
+        {
+            let launch_buys = self
+                .launch_buys
+                .as_mut()
+                .ok_or(ContractError::LaunchBuysRequired)?;
+            launch_buys
+                .record_buy(quote.token_amount)
                 .ok_or(ContractError::CalculationError)?;
+            require!(
+                launch_buys.tokens_bought <= self.bonding_curve.launch_window_max_tokens,
+                ContractError::LaunchWindowCapExceeded
+            );
         }
-        //Collect Fee
-        // Transfer SOL to fee recipient
-        let fee_transfer_instruction = system_instruction::transfer(
-            ctx.accounts.user.key,
-            &ctx.accounts.fee_receiver.key(),
-            fee_lamports,
-        );
 
-        anchor_lang::solana_program::program::invoke_signed(
-            &fee_transfer_instruction,
-            &[
-                ctx.accounts.user.to_account_info(),
-                ctx.accounts.fee_receiver.to_account_info(),
-                ctx.accounts.system_program.to_account_info(),
-            ],
+        //Collect Fee, split between the protocol, the creator and the referrer
+        let fees = distribute_fees(
+            FeeAccounts {
+                payer: self.user.to_account_info(),
+                fee_vault: &mut self.fee_vault,
+                creator_vault: &mut self.creator_vault,
+                referrer: self.referrer.clone(),
+                referrer_stats: self.referrer_stats.as_deref_mut(),
+                system_program: self.system_program.to_account_info(),
+            },
+            &self.global,
+            quote.fee,
+            quote.sol_amount,
             &[],
         )?;
 
-        //Deduct SOL
+        //Deduct SOL, on the last buy only the recomputed amount is taken
         let transfer_instruction = system_instruction::transfer(
-            ctx.accounts.user.key,
-            ctx.accounts.bonding_curve_sol_escrow.to_account_info().key,
-            sol_amount,
+            self.user.key,
+            self.bonding_curve_sol_escrow.to_account_info().key,
+            quote.sol_amount,
         );
 
         anchor_lang::solana_program::program::invoke_signed(
             &transfer_instruction,
             &[
-                ctx.accounts.user.to_account_info(),
-                ctx.accounts.bonding_curve_sol_escrow.to_account_info(),
-                ctx.accounts.system_program.to_account_info(),
+                self.user.to_account_info(),
+                self.bonding_curve_sol_escrow.to_account_info(),
+                self.system_program.to_account_info(),
             ],
             &[],
         )?;
 
         //Send Token
         // Transfer tokens to user
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.bonding_curve_token_account.to_account_info(),
-            to: ctx.accounts.user_token_account.to_account_info(),
-            authority: bonding_curve.to_account_info(),
+        let cpi_accounts = TransferChecked {
+            from: self.bonding_curve_token_account.to_account_info(),
+            mint: self.mint.to_account_info(),
+            to: self.user_token_account.to_account_info(),
+            authority: self.bonding_curve.to_account_info(),
         };
-        let mint_key = ctx.accounts.mint.key();
-        let mint_auth_signer_seeds: &[&[&[u8]]] = &[&[
-            b"bonding-curve",
-            &mint_key.as_ref(),
-            &[ctx.bumps.bonding_curve],
-        ]];
-
-        token::transfer(
+        let mint_key = self.mint.key();
+        let mint_auth_signer_seeds: &[&[&[u8]]] =
+            &[&[b"bonding-curve", mint_key.as_ref(), &[bonding_curve_bump]]];
+
+        token_interface::transfer_checked(
             CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
+                self.token_program.to_account_info(),
                 cpi_accounts,
                 mint_auth_signer_seeds,
             ),
-            token_amount,
+            quote.token_amount,
+            self.mint.decimals,
         )?;
 
-        //Update Reserves
-        if last_buy == true {
-            bonding_curve.complete = true;
-        }
+        //Update Reserves, completes the curve on the last buy
+        let bonding_curve = &mut self.bonding_curve;
+        bonding_curve.apply_quote(quote);
 
-        //Update Reserves
-        bonding_curve.update_reserves_after_buy(token_amount, calc_sol_amount);
+        let timestamp = Clock::get()?.unix_timestamp;
+        emit!(TradeEvent {
+            mint: self.mint.key(),
+            user: self.user.key(),
+            is_buy: true,
+            sol_amount: quote.sol_amount,
+            token_amount: quote.token_amount,
+            fee: fees.total,
+            creator_fee: fees.creator,
+            referral_fee: fees.referral,
+            virtual_sol_reserves: bonding_curve.virtual_sol_reserves,
+            virtual_token_reserves: bonding_curve.virtual_token_reserves,
+            real_sol_reserves: bonding_curve.real_sol_reserves,
+            real_token_reserves: bonding_curve.real_token_reserves,
+            timestamp,
+        });
+
+        if quote.complete {
+            emit!(CompleteEvent {
+                mint: self.mint.key(),
+                user: self.user.key(),
+                bonding_curve: bonding_curve.key(),
+                timestamp,
+            });
+        }
 
         Ok(())
     }
@@ -0,0 +1,30 @@
+use crate::errors::*;
+use crate::instructions::buy::*;
+use anchor_lang::prelude::*;
+
+//Takes the same accounts as a buy, only the quote differs
+pub struct BuyExactTokens;
+
+impl BuyExactTokens {
+    pub fn handler(ctx: Context<Buy>, token_amount: u64, max_sol_cost: u64) -> Result<()> {
+        //validate
+        ctx.accounts.validate(token_amount)?;
+        msg!("input token amount: {}", token_amount);
+
+        //calculate sol to be paid, same path as quote_buy_exact_tokens
+        let quote = ctx
+            .accounts
+            .bonding_curve
+            .quote_buy_exact_tokens(&ctx.accounts.global, token_amount)
+            .ok_or(ContractError::CalculationError)?;
+        msg!("this is the sol amount: {}", quote.sol_amount);
+
+        //Slippage Check
+        require!(
+            quote.sol_amount <= max_sol_cost,
+            ContractError::TooMuchSolRequired
+        );
+
+        ctx.accounts.execute_buy(&quote, ctx.bumps.bonding_curve)
+    }
+}
@@ -0,0 +1,50 @@
+use crate::errors::*;
+use crate::events::*;
+use crate::states::creator_vault::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ClaimCreatorFees<'info> {
+    #[account(mut)]
+    creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CreatorVault::SEED_PREFIX.as_bytes(), creator.key().as_ref()],
+        bump = creator_vault.bump,
+        has_one = creator @ ContractError::WrongAuthority,
+    )]
+    creator_vault: Box<Account<'info, CreatorVault>>,
+}
+
+impl ClaimCreatorFees<'_> {
+    pub fn handler(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+        //everything above the rent exempt minimum is claimable
+        let min_rent = Rent::get()?.minimum_balance(8 + CreatorVault::INIT_SPACE);
+        let amount = ctx
+            .accounts
+            .creator_vault
+            .get_lamports()
+            .saturating_sub(min_rent);
+        require!(amount > 0, ContractError::InsufficientVaultFees);
+
+        //vault is owned by this program so lamports are moved directly
+        ctx.accounts.creator_vault.sub_lamports(amount)?;
+        ctx.accounts.creator.add_lamports(amount)?;
+
+        ctx.accounts
+            .creator_vault
+            .record_claim(amount)
+            .ok_or(ContractError::CalculationError)?;
+
+        emit!(ClaimCreatorFeesEvent {
+            creator: ctx.accounts.creator.key(),
+            amount,
+            total_fees_accrued: ctx.accounts.creator_vault.total_fees_accrued,
+            total_fees_claimed: ctx.accounts.creator_vault.total_fees_claimed,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
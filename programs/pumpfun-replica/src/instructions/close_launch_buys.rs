@@ -0,0 +1,46 @@
+use crate::errors::*;
+use crate::states::{bonding_curve::*, global::*, launch_buys::*};
+use anchor_lang::prelude::*;
+
+use anchor_spl::token_interface::Mint;
+
+#[derive(Accounts)]
+pub struct CloseLaunchBuys<'info> {
+    #[account(mut)]
+    user: Signer<'info>,
+
+    #[account(
+        seeds = [Global::SEED_PREFIX.as_bytes()],
+        bump,
+    )]
+    global: Box<Account<'info, Global>>,
+
+    mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [BondingCurve::SEED_PREFIX.as_bytes(), mint.to_account_info().key.as_ref()],
+        bump,
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [LaunchBuys::SEED_PREFIX.as_bytes(), bonding_curve.key().as_ref(), user.key().as_ref()],
+        bump = launch_buys.bump,
+    )]
+    launch_buys: Box<Account<'info, LaunchBuys>>,
+}
+
+impl CloseLaunchBuys<'_> {
+    //Refunds the tally's rent to the wallet once the launch window is over
+    pub fn handler(ctx: Context<CloseLaunchBuys>) -> Result<()> {
+        require!(
+            !ctx.accounts
+                .bonding_curve
+                .in_launch_window(Clock::get()?.unix_timestamp),
+            ContractError::LaunchWindowOpen
+        );
+        Ok(())
+    }
+}
@@ -1,15 +1,16 @@
 use crate::errors::*;
-use crate::states::{bonding_curve::*, global::*};
+use crate::states::{bonding_curve::*, creator_vault::*, global::*};
+use crate::utils::launch::*;
 use anchor_lang::prelude::*;
 
 use anchor_spl::metadata::Metadata;
-use anchor_spl::token_interface::spl_token_metadata_interface::state::TokenMetadata;
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    metadata_pointer::MetadataPointer, BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+};
 use anchor_spl::{
     associated_token::AssociatedToken,
-    metadata::{
-        create_metadata_accounts_v3, mpl_token_metadata::types::DataV2, CreateMetadataAccountsV3,
-    },
-    token::{mint_to, Mint, MintTo, Token, TokenAccount},
+    token_2022::{spl_token_2022, Token2022},
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
 #[derive(Accounts)]
@@ -18,10 +19,12 @@ pub struct CreateBondingCurve<'info> {
         mut,
         constraint = mint.decimals == global.mint_decimals @ ContractError::InvalidMintDecimals,
         constraint = mint.mint_authority == Some(bonding_curve.key()).into() @ContractError::WrongAuthority,
+        constraint = mint.freeze_authority.is_none()
+            || mint.freeze_authority == Some(bonding_curve.key()).into() @ContractError::InvalidFreezeAuthority,
         // mint::authority = bonding_curve,
         // mint::freeze_authority = bonding_curve
     )]
-    mint: Box<Account<'info, Mint>>,
+    mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(mut)]
     creator: Signer<'info>,
@@ -39,107 +42,140 @@ pub struct CreateBondingCurve<'info> {
         init_if_needed,
         payer = creator,
         associated_token::mint = mint,
-        associated_token::authority = bonding_curve
+        associated_token::authority = bonding_curve,
+        associated_token::token_program = token_program,
     )]
-    bonding_curve_token_account: Box<Account<'info, TokenAccount>>,
+    bonding_curve_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
+        mut,
         seeds = [b"sol-escrow", mint.key().as_ref()],
         bump,
     )]
     bonding_curve_sol_escrow: SystemAccount<'info>,
 
+    //Shared by all of the creator's curves, created on their first launch
+    #[account(
+        init_if_needed,
+        payer = creator,
+        seeds = [b"creator-vault", creator.key().as_ref()],
+        space = 8 + CreatorVault::INIT_SPACE,
+        bump
+    )]
+    creator_vault: Box<Account<'info, CreatorVault>>,
+
     #[account(
         seeds = [b"global"],
-        constraint = global.initialized == true @ ContractError::NotInitialized,
+        constraint = global.initialized @ ContractError::NotInitialized,
+        constraint = !global.paused @ ContractError::GlobalPaused,
         bump,
     )]
     global: Box<Account<'info, Global>>,
 
+    //Metaplex accounts, only used for SPL Token mints.
+    //Token-2022 mints keep their metadata on the mint itself.
     #[account(mut)]
     //research about adding this part, current implementation
     ///CHECK: Using seed to validate metadata account
-    metadata: UncheckedAccount<'info>,
+    metadata: Option<UncheckedAccount<'info>>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     /// CHECK: token metadata program account
-    pub token_metadata_program: Program<'info, Metadata>,
+    pub token_metadata_program: Option<Program<'info, Metadata>>,
     // research if this rent account is necessary
     /// CHECK: rent account
-    pub rent: UncheckedAccount<'info>,
+    pub rent: Option<UncheckedAccount<'info>>,
 }
 
 impl CreateBondingCurve<'_> {
+    pub fn validate(&self) -> Result<()> {
+        //Only the metadata extensions are allowed, anything else (transfer fees, hooks,
+        //permanent delegates...) would break the curve accounting
+        let mint_info = self.mint.to_account_info();
+        let mint_data = mint_info.try_borrow_data()?;
+        let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+        for extension in mint_state.get_extension_types()? {
+            require!(
+                matches!(
+                    extension,
+                    ExtensionType::MetadataPointer | ExtensionType::TokenMetadata
+                ),
+                ContractError::UnsupportedMintExtension
+            );
+        }
+
+        //Token-2022 metadata is written to the mint, so the pointer wallets follow
+        //must target the mint itself and not some externally controlled account
+        if self.token_program.key() == Token2022::id() {
+            let metadata_pointer = mint_state
+                .get_extension::<MetadataPointer>()
+                .map_err(|_| ContractError::InvalidMetadataPointer)?;
+            require!(
+                Option::<Pubkey>::from(metadata_pointer.metadata_address) == Some(self.mint.key()),
+                ContractError::InvalidMetadataPointer
+            );
+        }
+
+        Ok(())
+    }
+
     pub fn handler(
         ctx: Context<CreateBondingCurve>,
         params: CreateBondingCurveParams,
     ) -> Result<()> {
-        //Initialize Bonding Curve
-        ctx.accounts.bonding_curve.update_from_params(
-            ctx.accounts.mint.key(),
-            ctx.accounts.creator.key(),
-            &ctx.accounts.global,
-            ctx.bumps.bonding_curve,
-        );
+        //validate
+        ctx.accounts.validate()?;
 
         let mint_key = ctx.accounts.mint.key();
         let mint_auth_signer_seeds: &[&[&[u8]]] = &[&[
             b"bonding-curve",
-            &mint_key.as_ref(),
+            mint_key.as_ref(),
             &[ctx.bumps.bonding_curve],
         ]];
+        let is_token_2022 = ctx.accounts.token_program.key() == Token2022::id();
+        let has_freeze_authority = ctx.accounts.mint.freeze_authority.is_some();
+
+        let accounts = &mut *ctx.accounts;
+        let mut launch = CurveLaunch {
+            creator: accounts.creator.to_account_info(),
+            mint: accounts.mint.to_account_info(),
+            bonding_curve: &mut accounts.bonding_curve,
+            bonding_curve_token_account: accounts.bonding_curve_token_account.to_account_info(),
+            bonding_curve_sol_escrow: accounts.bonding_curve_sol_escrow.to_account_info(),
+            creator_vault: &mut accounts.creator_vault,
+            global: &accounts.global,
+            system_program: accounts.system_program.to_account_info(),
+            token_program: accounts.token_program.to_account_info(),
+        };
+
+        //Initialize Bonding Curve
+        launch.initialize(&params, ctx.bumps.bonding_curve, ctx.bumps.creator_vault)?;
 
         //Create Token Metadata
-        ctx.accounts.set_metadata(mint_auth_signer_seeds, &params)?;
-
-        //Mint Tokens
-        mint_to(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                MintTo {
-                    authority: ctx.accounts.bonding_curve.to_account_info(),
-                    to: ctx.accounts.bonding_curve_token_account.to_account_info(),
-                    mint: ctx.accounts.mint.to_account_info(),
-                },
+        if is_token_2022 {
+            launch.set_token_metadata(mint_auth_signer_seeds, &params)?;
+        } else {
+            let (Some(metadata), Some(token_metadata_program), Some(rent)) = (
+                &accounts.metadata,
+                &accounts.token_metadata_program,
+                &accounts.rent,
+            ) else {
+                return err!(ContractError::MissingMetadataAccounts);
+            };
+            launch.set_metadata(
+                metadata.to_account_info(),
+                token_metadata_program.to_account_info(),
+                rent.to_account_info(),
                 mint_auth_signer_seeds,
-            ),
-            ctx.accounts.bonding_curve.token_total_supply,
-        )?;
-        //Lock Curve and Revoke Authorities
-        Ok(())
-    }
+                &params,
+            )?;
+        }
 
-    pub fn set_metadata(
-        &mut self,
-        mint_auth_signer_seeds: &[&[&[u8]]],
-        params: &CreateBondingCurveParams,
-    ) -> Result<()> {
-        let token_data: DataV2 = DataV2 {
-            name: params.name.clone(),
-            symbol: params.symbol.clone(),
-            uri: params.uri.clone(),
-            seller_fee_basis_points: 0,
-            creators: None,
-            collection: None,
-            uses: None,
-        };
-        let metadata_ctx = CpiContext::new_with_signer(
-            self.token_metadata_program.to_account_info(),
-            CreateMetadataAccountsV3 {
-                payer: self.creator.to_account_info(),
-                mint: self.mint.to_account_info().clone(),
-                metadata: self.metadata.to_account_info().clone(),
-                update_authority: self.bonding_curve.to_account_info().clone(),
-                mint_authority: self.bonding_curve.to_account_info().clone(),
-                system_program: self.system_program.to_account_info(),
-                rent: self.rent.to_account_info(),
-            },
-            mint_auth_signer_seeds,
-        );
-        create_metadata_accounts_v3(metadata_ctx, token_data, false, true, None)?;
-        //msg!("CreateBondingCurve::intialize_meta: done");
-        Ok(())
+        //Mint Tokens, Lock Curve and Revoke Authorities
+        launch.mint_and_lock(mint_auth_signer_seeds, has_freeze_authority)?;
+
+        launch.emit_create_event(params)
     }
 }
@@ -0,0 +1,123 @@
+use crate::errors::*;
+use crate::states::{bonding_curve::*, creator_vault::*, global::*};
+use crate::utils::launch::*;
+use anchor_lang::prelude::*;
+
+use anchor_spl::metadata::Metadata;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct CreateBondingCurveWithMint<'info> {
+    #[account(mut)]
+    creator: Signer<'info>,
+
+    #[account(
+        seeds = [b"global"],
+        constraint = global.initialized @ ContractError::NotInitialized,
+        constraint = !global.paused @ ContractError::GlobalPaused,
+        bump,
+    )]
+    global: Box<Account<'info, Global>>,
+
+    //Fresh keypair, the program initialises it with the bonding curve as mint authority
+    #[account(
+        init,
+        payer = creator,
+        mint::decimals = global.mint_decimals,
+        mint::authority = bonding_curve,
+    )]
+    mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"bonding-curve", mint.key().as_ref()],
+        space = 8 + BondingCurve::INIT_SPACE,
+        bump
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    #[account(
+        init,
+        payer = creator,
+        associated_token::mint = mint,
+        associated_token::authority = bonding_curve
+    )]
+    bonding_curve_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"sol-escrow", mint.key().as_ref()],
+        bump,
+    )]
+    bonding_curve_sol_escrow: SystemAccount<'info>,
+
+    //Shared by all of the creator's curves, created on their first launch
+    #[account(
+        init_if_needed,
+        payer = creator,
+        seeds = [b"creator-vault", creator.key().as_ref()],
+        space = 8 + CreatorVault::INIT_SPACE,
+        bump
+    )]
+    creator_vault: Box<Account<'info, CreatorVault>>,
+
+    #[account(mut)]
+    ///CHECK: Using seed to validate metadata account
+    metadata: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    /// CHECK: token metadata program account
+    pub token_metadata_program: Program<'info, Metadata>,
+    /// CHECK: rent account
+    pub rent: UncheckedAccount<'info>,
+}
+
+impl CreateBondingCurveWithMint<'_> {
+    pub fn handler(
+        ctx: Context<CreateBondingCurveWithMint>,
+        params: CreateBondingCurveParams,
+    ) -> Result<()> {
+        let mint_key = ctx.accounts.mint.key();
+        let mint_auth_signer_seeds: &[&[&[u8]]] = &[&[
+            b"bonding-curve",
+            mint_key.as_ref(),
+            &[ctx.bumps.bonding_curve],
+        ]];
+
+        let accounts = &mut *ctx.accounts;
+        let mut launch = CurveLaunch {
+            creator: accounts.creator.to_account_info(),
+            mint: accounts.mint.to_account_info(),
+            bonding_curve: &mut accounts.bonding_curve,
+            bonding_curve_token_account: accounts.bonding_curve_token_account.to_account_info(),
+            bonding_curve_sol_escrow: accounts.bonding_curve_sol_escrow.to_account_info(),
+            creator_vault: &mut accounts.creator_vault,
+            global: &accounts.global,
+            system_program: accounts.system_program.to_account_info(),
+            token_program: accounts.token_program.to_account_info(),
+        };
+
+        //Initialize Bonding Curve
+        launch.initialize(&params, ctx.bumps.bonding_curve, ctx.bumps.creator_vault)?;
+
+        //Create Token Metadata
+        launch.set_metadata(
+            accounts.metadata.to_account_info(),
+            accounts.token_metadata_program.to_account_info(),
+            accounts.rent.to_account_info(),
+            mint_auth_signer_seeds,
+            &params,
+        )?;
+
+        //Mint Tokens, Lock Curve and Revoke Authorities, the mint is created without a freeze authority
+        launch.mint_and_lock(mint_auth_signer_seeds, false)?;
+
+        launch.emit_create_event(params)
+    }
+}
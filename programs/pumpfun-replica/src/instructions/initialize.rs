@@ -1,6 +1,6 @@
 //configuration
 
-use crate::states::global::*;
+use crate::states::{fee_vault::*, global::*};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -17,15 +17,28 @@ pub struct Initialize<'info> {
     )]
     global: Box<Account<'info, Global>>,
 
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + FeeVault::INIT_SPACE,
+        seeds = [b"fee-vault"],
+        bump
+    )]
+    fee_vault: Box<Account<'info, FeeVault>>,
+
     system_program: Program<'info, System>,
 }
 
 pub fn initialize(ctx: Context<Initialize>, params: GlobalSettingsInput) -> Result<()> {
+    params.validate()?;
+
     let global = &mut ctx.accounts.global;
 
     global.update_settings(params);
     global.migration_authority = ctx.accounts.authority.key();
     global.global_authority = ctx.accounts.authority.key();
     global.initialized = true;
+
+    ctx.accounts.fee_vault.bump = ctx.bumps.fee_vault;
     Ok(())
 }
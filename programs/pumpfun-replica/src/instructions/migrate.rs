@@ -0,0 +1,208 @@
+use crate::errors::*;
+use crate::events::*;
+use crate::states::{bonding_curve::*, fee_vault::*, global::*};
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program::invoke_signed,
+        system_instruction,
+    },
+};
+
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+//Discriminator of `initialize_pool(sol_amount: u64, token_amount: u64)`, the entrypoint
+//the program set as lp_config must expose to receive migrated liquidity
+const INITIALIZE_POOL_DISCRIMINATOR: [u8; 8] = [95, 180, 10, 172, 84, 174, 232, 40];
+
+#[derive(Accounts)]
+pub struct Migrate<'info> {
+    #[account(
+        mut,
+        constraint = migration_authority.key() == global.migration_authority @ ContractError::WrongAuthority,
+    )]
+    migration_authority: Signer<'info>,
+
+    #[account(
+        seeds = [Global::SEED_PREFIX.as_bytes()],
+        constraint = global.initialized @ ContractError::NotInitialized,
+        bump,
+    )]
+    global: Box<Account<'info, Global>>,
+
+    #[account(
+        mut,
+        seeds = [FeeVault::SEED_PREFIX.as_bytes()],
+        bump = fee_vault.bump,
+    )]
+    fee_vault: Box<Account<'info, FeeVault>>,
+
+    mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [BondingCurve::SEED_PREFIX.as_bytes(), mint.to_account_info().key.as_ref()],
+        constraint = bonding_curve.complete @ ContractError::BondingCurveNotComplete,
+        constraint = !bonding_curve.migrated @ ContractError::BondingCurveMigrated,
+        bump,
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = bonding_curve,
+        associated_token::token_program = token_program,
+    )]
+    bonding_curve_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [BondingCurve::SOL_ESCROW_SEED_PREFIX.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
+    /// CHECK: PDA to hold SOL for bonding curve
+    pub bonding_curve_sol_escrow: AccountInfo<'info>,
+
+    #[account(mut)]
+    /// CHECK: Pool PDA, validated and initialized by the AMM program
+    pool: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Pool token account, validated and initialized by the AMM program
+    pool_token_account: UncheckedAccount<'info>,
+
+    #[account(
+        executable,
+        constraint = amm_program.key() == global.lp_config @ ContractError::InvalidLpConfig,
+    )]
+    /// CHECK: AMM program configured as lp_config on global
+    amm_program: UncheckedAccount<'info>,
+
+    system_program: Program<'info, System>,
+
+    token_program: Interface<'info, TokenInterface>,
+
+    associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl Migrate<'_> {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.bonding_curve_token_account.amount
+                >= self.bonding_curve.migration_token_allocation,
+            ContractError::InsufficientMigrationTokens
+        );
+
+        Ok(())
+    }
+
+    pub fn handler(ctx: Context<Migrate>) -> Result<()> {
+        //validate
+        ctx.accounts.validate()?;
+
+        let fee_lamports = ctx.accounts.bonding_curve.migrate_fee_amount;
+        let token_amount = ctx.accounts.bonding_curve.migration_token_allocation;
+        let sol_amount = ctx
+            .accounts
+            .bonding_curve
+            .real_sol_reserves
+            .checked_sub(fee_lamports)
+            .ok_or(ContractError::CalculationError)?;
+
+        //GENERATE SIGNER SEEDS
+        let mint_key = ctx.accounts.mint.key();
+        let sol_escrow_seeds: &[&[u8]] = &[
+            b"sol-escrow",
+            mint_key.as_ref(),
+            &[ctx.bumps.bonding_curve_sol_escrow],
+        ];
+        let bonding_curve_seeds: &[&[u8]] = &[
+            b"bonding-curve",
+            mint_key.as_ref(),
+            &[ctx.bumps.bonding_curve],
+        ];
+
+        //Collect migration fee
+        let fee_transfer_instruction = system_instruction::transfer(
+            ctx.accounts.bonding_curve_sol_escrow.to_account_info().key,
+            &ctx.accounts.fee_vault.key(),
+            fee_lamports,
+        );
+
+        invoke_signed(
+            &fee_transfer_instruction,
+            &[
+                ctx.accounts.bonding_curve_sol_escrow.to_account_info(),
+                ctx.accounts.fee_vault.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+            &[sol_escrow_seeds],
+        )?;
+
+        ctx.accounts
+            .fee_vault
+            .record_fee(fee_lamports)
+            .ok_or(ContractError::CalculationError)?;
+
+        //Seed pool with escrowed SOL and the migration token allocation
+        let mut data = INITIALIZE_POOL_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&sol_amount.to_le_bytes());
+        data.extend_from_slice(&token_amount.to_le_bytes());
+
+        let initialize_pool_instruction = Instruction {
+            program_id: ctx.accounts.amm_program.key(),
+            accounts: vec![
+                AccountMeta::new(ctx.accounts.migration_authority.key(), true),
+                AccountMeta::new(ctx.accounts.bonding_curve_sol_escrow.key(), true),
+                AccountMeta::new_readonly(ctx.accounts.bonding_curve.key(), true),
+                AccountMeta::new_readonly(ctx.accounts.mint.key(), false),
+                AccountMeta::new(ctx.accounts.pool.key(), false),
+                AccountMeta::new(ctx.accounts.pool_token_account.key(), false),
+                AccountMeta::new(ctx.accounts.bonding_curve_token_account.key(), false),
+                AccountMeta::new_readonly(ctx.accounts.system_program.key(), false),
+                AccountMeta::new_readonly(ctx.accounts.token_program.key(), false),
+                AccountMeta::new_readonly(ctx.accounts.associated_token_program.key(), false),
+            ],
+            data,
+        };
+
+        invoke_signed(
+            &initialize_pool_instruction,
+            &[
+                ctx.accounts.migration_authority.to_account_info(),
+                ctx.accounts.bonding_curve_sol_escrow.to_account_info(),
+                ctx.accounts.bonding_curve.to_account_info(),
+                ctx.accounts.mint.to_account_info(),
+                ctx.accounts.pool.to_account_info(),
+                ctx.accounts.pool_token_account.to_account_info(),
+                ctx.accounts.bonding_curve_token_account.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.associated_token_program.to_account_info(),
+                ctx.accounts.amm_program.to_account_info(),
+            ],
+            &[sol_escrow_seeds, bonding_curve_seeds],
+        )?;
+
+        //Lock Curve
+        let bonding_curve = &mut ctx.accounts.bonding_curve;
+        bonding_curve.real_sol_reserves = 0;
+        bonding_curve.migrated = true;
+
+        emit!(MigrateEvent {
+            mint: ctx.accounts.mint.key(),
+            pool: ctx.accounts.pool.key(),
+            sol_amount,
+            token_amount,
+            fee: fee_lamports,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
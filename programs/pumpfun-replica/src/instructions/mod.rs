@@ -1,11 +1,62 @@
+pub use accept_authority::*;
+pub mod accept_authority;
+
 pub use buy::*;
 pub mod buy;
 
+pub use buy_exact_tokens::*;
+pub mod buy_exact_tokens;
+
+pub use claim_creator_fees::*;
+pub mod claim_creator_fees;
+
+pub use close_launch_buys::*;
+pub mod close_launch_buys;
+
 pub use create_bonding_curve::*;
 pub mod create_bonding_curve;
 
+pub use create_bonding_curve_with_mint::*;
+pub mod create_bonding_curve_with_mint;
+
 pub use initialize::*;
 pub mod initialize;
 
+pub use set_params::*;
+pub mod set_params;
+
+pub use set_paused::*;
+pub mod set_paused;
+
+pub use set_halted::*;
+pub mod set_halted;
+
+pub use migrate::*;
+pub mod migrate;
+
+pub use open_launch_buys::*;
+pub mod open_launch_buys;
+
+pub use propose_authority::*;
+pub mod propose_authority;
+
+pub use quote_buy::*;
+pub mod quote_buy;
+
+pub use quote_buy_exact_tokens::*;
+pub mod quote_buy_exact_tokens;
+
+pub use quote_sell::*;
+pub mod quote_sell;
+
+pub use register_referrer::*;
+pub mod register_referrer;
+
 pub use sell::*;
 pub mod sell;
+
+pub use sell_for_exact_sol::*;
+pub mod sell_for_exact_sol;
+
+pub use withdraw_fees::*;
+pub mod withdraw_fees;
@@ -0,0 +1,55 @@
+use crate::errors::*;
+use crate::states::{bonding_curve::*, global::*, launch_buys::*};
+use anchor_lang::prelude::*;
+
+use anchor_spl::token_interface::Mint;
+
+#[derive(Accounts)]
+pub struct OpenLaunchBuys<'info> {
+    #[account(mut)]
+    user: Signer<'info>,
+
+    #[account(
+        seeds = [Global::SEED_PREFIX.as_bytes()],
+        constraint = global.initialized @ ContractError::NotInitialized,
+        bump,
+    )]
+    global: Box<Account<'info, Global>>,
+
+    mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [BondingCurve::SEED_PREFIX.as_bytes(), mint.to_account_info().key.as_ref()],
+        bump,
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + LaunchBuys::INIT_SPACE,
+        seeds = [LaunchBuys::SEED_PREFIX.as_bytes(), bonding_curve.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    launch_buys: Box<Account<'info, LaunchBuys>>,
+
+    system_program: Program<'info, System>,
+}
+
+impl OpenLaunchBuys<'_> {
+    //Wallets open their tally before buying in the launch window, it is not needed after
+    pub fn handler(ctx: Context<OpenLaunchBuys>) -> Result<()> {
+        require!(
+            ctx.accounts
+                .bonding_curve
+                .in_launch_window(Clock::get()?.unix_timestamp),
+            ContractError::LaunchWindowClosed
+        );
+
+        let launch_buys = &mut ctx.accounts.launch_buys;
+        launch_buys.bonding_curve = ctx.accounts.bonding_curve.key();
+        launch_buys.user = ctx.accounts.user.key();
+        launch_buys.bump = ctx.bumps.launch_buys;
+        Ok(())
+    }
+}
@@ -0,0 +1,34 @@
+use crate::errors::*;
+use crate::states::global::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Global::SEED_PREFIX.as_bytes()],
+        constraint = global.initialized @ ContractError::NotInitialized,
+        bump,
+    )]
+    global: Box<Account<'info, Global>>,
+}
+
+impl ProposeAuthority<'_> {
+    //Proposing Pubkey::default() cancels a pending transfer
+    pub fn handler(
+        ctx: Context<ProposeAuthority>,
+        role: AuthorityRole,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        let global = &mut ctx.accounts.global;
+        require!(
+            global.current_authority(role) == ctx.accounts.authority.key(),
+            ContractError::WrongAuthority
+        );
+
+        global.propose_authority(role, new_authority);
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+use crate::errors::*;
+use crate::states::{bonding_curve::*, global::*};
+use anchor_lang::prelude::*;
+
+use anchor_spl::token_interface::Mint;
+
+//Read only, lets integrators and other programs price an exact token buy before sending it
+#[derive(Accounts)]
+pub struct QuoteBuyExactTokens<'info> {
+    #[account(
+        seeds = [Global::SEED_PREFIX.as_bytes()],
+        constraint = global.initialized @ ContractError::NotInitialized,
+        bump,
+    )]
+    global: Box<Account<'info, Global>>,
+
+    mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [BondingCurve::SEED_PREFIX.as_bytes(), mint.to_account_info().key.as_ref()],
+        constraint = !bonding_curve.complete @ ContractError::BondingCurveComplete,
+        bump,
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+}
+
+impl QuoteBuyExactTokens<'_> {
+    pub fn handler(ctx: Context<QuoteBuyExactTokens>, token_amount: u64) -> Result<TradeQuote> {
+        require!(token_amount > 0, ContractError::MinBuy);
+
+        let quote = ctx
+            .accounts
+            .bonding_curve
+            .quote_buy_exact_tokens(&ctx.accounts.global, token_amount)
+            .ok_or(ContractError::CalculationError)?;
+        Ok(quote)
+    }
+}
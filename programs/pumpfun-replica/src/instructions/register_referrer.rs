@@ -0,0 +1,29 @@
+use crate::states::referrer_stats::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct RegisterReferrer<'info> {
+    #[account(mut)]
+    referrer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = referrer,
+        space = 8 + ReferrerStats::INIT_SPACE,
+        seeds = [b"referrer-stats", referrer.key().as_ref()],
+        bump
+    )]
+    referrer_stats: Box<Account<'info, ReferrerStats>>,
+
+    system_program: Program<'info, System>,
+}
+
+impl RegisterReferrer<'_> {
+    //Referrers register once before trades can be routed through them
+    pub fn handler(ctx: Context<RegisterReferrer>) -> Result<()> {
+        let referrer_stats = &mut ctx.accounts.referrer_stats;
+        referrer_stats.referrer = ctx.accounts.referrer.key();
+        referrer_stats.bump = ctx.bumps.referrer_stats;
+        Ok(())
+    }
+}
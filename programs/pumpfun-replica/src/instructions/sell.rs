@@ -1,5 +1,9 @@
 use crate::errors::*;
-use crate::states::{bonding_curve::*, global::*};
+use crate::events::*;
+use crate::states::{
+    bonding_curve::*, creator_vault::*, fee_vault::*, global::*, referrer_stats::*,
+};
+use crate::utils::fees::*;
 use anchor_lang::{
     prelude::*,
     solana_program::{program::invoke_signed, system_instruction},
@@ -7,7 +11,7 @@ use anchor_lang::{
 
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{self, Mint, Token, TokenAccount, Transfer},
+    token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
 #[derive(Accounts)]
@@ -17,31 +21,56 @@ pub struct Sell<'info> {
 
     #[account(
         seeds = [Global::SEED_PREFIX.as_bytes()],
-        constraint = global.initialized == true @ ContractError::NotInitialized,
+        constraint = global.initialized @ ContractError::NotInitialized,
+        constraint = !global.paused @ ContractError::GlobalPaused,
         bump,
     )]
-    global: Box<Account<'info, Global>>,
+    pub global: Box<Account<'info, Global>>,
+
+    #[account(
+        mut,
+        seeds = [FeeVault::SEED_PREFIX.as_bytes()],
+        bump = fee_vault.bump,
+    )]
+    fee_vault: Box<Account<'info, FeeVault>>,
+
+    #[account(
+        mut,
+        seeds = [CreatorVault::SEED_PREFIX.as_bytes(), bonding_curve.creator.as_ref()],
+        bump = creator_vault.bump,
+    )]
+    creator_vault: Box<Account<'info, CreatorVault>>,
 
+    //Optional referrer routing the trade, paid a share of the protocol fee
     #[account(mut)]
-    ///CHECK: Receiver for FEES
-    fee_receiver: AccountInfo<'info>,
+    ///CHECK: Wallet registered as a referrer, matched against referrer_stats
+    referrer: Option<AccountInfo<'info>>,
 
-    mint: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [ReferrerStats::SEED_PREFIX.as_bytes(), referrer_stats.referrer.as_ref()],
+        bump = referrer_stats.bump,
+    )]
+    referrer_stats: Option<Box<Account<'info, ReferrerStats>>>,
+
+    mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
         seeds = [BondingCurve::SEED_PREFIX.as_bytes(), mint.to_account_info().key.as_ref()],
-        constraint = bonding_curve.complete == false @ ContractError::BondingCurveComplete,
+        constraint = !bonding_curve.complete @ ContractError::BondingCurveComplete,
+        constraint = !bonding_curve.halted @ ContractError::BondingCurveHalted,
         bump,
     )]
-    bonding_curve: Box<Account<'info, BondingCurve>>,
+    pub bonding_curve: Box<Account<'info, BondingCurve>>,
 
     #[account(
         mut,
         associated_token::mint = mint,
         associated_token::authority = bonding_curve,
+        associated_token::token_program = token_program,
     )]
-    bonding_curve_token_account: Box<Account<'info, TokenAccount>>,
+    bonding_curve_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
@@ -55,12 +84,13 @@ pub struct Sell<'info> {
         mut,
         associated_token::mint = mint,
         associated_token::authority = user,
+        associated_token::token_program = token_program,
     )]
-    user_token_account: Box<Account<'info, TokenAccount>>,
+    user_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     system_program: Program<'info, System>,
 
-    token_program: Program<'info, Token>,
+    token_program: Interface<'info, TokenInterface>,
 
     associated_token_program: Program<'info, AssociatedToken>,
 }
@@ -69,96 +99,130 @@ impl Sell<'_> {
     pub fn validate(&self, amount: u64) -> Result<()> {
         require!(amount > 0, ContractError::MinSell);
 
-        require!(
-            self.fee_receiver.key() == self.global.fee_receiver,
-            ContractError::InvalidFeeReceiver
-        );
-        
-        require!(
-            self.user_token_account.amount >= amount,
-            ContractError::InsufficientUserTokens,
-        );
+        validate_referrer(
+            &self.user.key(),
+            self.referrer.as_ref(),
+            self.referrer_stats.as_deref(),
+        )?;
 
         Ok(())
     }
 
-    pub fn handler(ctx: Context<Sell>, token_amount: u64) -> Result<()> {
+    pub fn handler(ctx: Context<Sell>, token_amount: u64, min_sol_output: u64) -> Result<()> {
         //validate
         ctx.accounts.validate(token_amount)?;
 
-        let bonding_curve = &mut ctx.accounts.bonding_curve;
-
-        //calculate sol to be received for selling
-        let sol_amount = bonding_curve
-            .get_sol_for_sale_on_tokens(token_amount)
+        //calculate sol to be received for selling, same path as quote_sell
+        let quote = ctx
+            .accounts
+            .bonding_curve
+            .quote_sell(&ctx.accounts.global, token_amount)
             .ok_or(ContractError::CalculationError)?;
 
-        msg!("This is the sol amount {}", sol_amount);
+        msg!("This is the sol amount {}", quote.sol_amount);
+
+        //fee is taken out of the sale proceeds held in escrow, it rounds up but never
+        //past the proceeds since fee_basis_points stays below the divisor
+        let sell_amount_minus_fee = quote
+            .sol_amount
+            .checked_sub(quote.fee)
+            .ok_or(ContractError::CalculationError)?;
 
-        let fee_lamports = 1_000_000;
+        //Slippage Check
+        require!(
+            sell_amount_minus_fee >= min_sol_output,
+            ContractError::TooLittleSolReceived
+        );
 
-        let sell_amount_minus_fee = sol_amount - fee_lamports;
+        ctx.accounts
+            .execute_sell(&quote, ctx.bumps.bonding_curve_sol_escrow)
+    }
 
-        //Collect fees
-        //Transfer SOL to fee recipient
-        let fee_transfer_instruction = system_instruction::transfer(
-            ctx.accounts.user.key,
-            &ctx.accounts.fee_receiver.key(),
-            fee_lamports,
+    //Settles a quoted sell, shared by sell and sell_for_exact_sol: the user's tokens
+    //go to the curve and the escrow pays out the curve leg less the fee
+    pub fn execute_sell(&mut self, quote: &TradeQuote, sol_escrow_bump: u8) -> Result<()> {
+        require!(
+            self.user_token_account.amount >= quote.token_amount,
+            ContractError::InsufficientUserTokens,
         );
 
-        anchor_lang::solana_program::program::invoke_signed(
-          &fee_transfer_instruction,
-          &[
-              ctx.accounts.user.to_account_info(),
-              ctx.accounts.fee_receiver.to_account_info(),
-              ctx.accounts.system_program.to_account_info(),
-          ],
-          &[],
-      )?;
+        let sell_amount_minus_fee = quote
+            .sol_amount
+            .checked_sub(quote.fee)
+            .ok_or(ContractError::CalculationError)?;
 
         //Transfer TOKEN TO BONDING CURVE
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.user_token_account.to_account_info(),
-            to: ctx.accounts.bonding_curve_token_account.to_account_info(),
-            authority: ctx.accounts.user.to_account_info(),
+        let cpi_accounts = TransferChecked {
+            from: self.user_token_account.to_account_info(),
+            mint: self.mint.to_account_info(),
+            to: self.bonding_curve_token_account.to_account_info(),
+            authority: self.user.to_account_info(),
         };
-        token::transfer(
-            CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
-            token_amount,
+        token_interface::transfer_checked(
+            CpiContext::new(self.token_program.to_account_info(), cpi_accounts),
+            quote.token_amount,
+            self.mint.decimals,
         )?;
 
         //Transfer sol to user
         let transfer_instruction = system_instruction::transfer(
-            &ctx.accounts.bonding_curve_sol_escrow.to_account_info().key,
-            &ctx.accounts.user.to_account_info().key,
+            self.bonding_curve_sol_escrow.to_account_info().key,
+            self.user.to_account_info().key,
             sell_amount_minus_fee,
         );
 
         //GENERATE SIGNER SEEDS
-        let mint_key = ctx.accounts.mint.key();
-        let sol_escrow_signer_seeds: &[&[&[u8]]] = &[&[
-            b"sol-escrow",
-            mint_key.as_ref(),
-            &[ctx.bumps.bonding_curve_sol_escrow],
-        ]];
+        let mint_key = self.mint.key();
+        let sol_escrow_signer_seeds: &[&[&[u8]]] =
+            &[&[b"sol-escrow", mint_key.as_ref(), &[sol_escrow_bump]]];
 
         invoke_signed(
             &transfer_instruction,
             &[
-                ctx.accounts
-                    .bonding_curve_sol_escrow
-                    .to_account_info()
-                    .clone(),
-                ctx.accounts.user.to_account_info().clone(),
-                ctx.accounts.system_program.to_account_info(),
+                self.bonding_curve_sol_escrow.to_account_info(),
+                self.user.to_account_info(),
+                self.system_program.to_account_info(),
             ],
             sol_escrow_signer_seeds,
         )?;
 
+        //Collect Fee, split between the protocol, the creator and the referrer
+        let fees = distribute_fees(
+            FeeAccounts {
+                payer: self.bonding_curve_sol_escrow.to_account_info(),
+                fee_vault: &mut self.fee_vault,
+                creator_vault: &mut self.creator_vault,
+                referrer: self.referrer.clone(),
+                referrer_stats: self.referrer_stats.as_deref_mut(),
+                system_program: self.system_program.to_account_info(),
+            },
+            &self.global,
+            quote.fee,
+            quote.sol_amount,
+            sol_escrow_signer_seeds,
+        )?;
+
         //update reserves
-        bonding_curve.update_reserves_after_sell(token_amount, sol_amount);
+        let bonding_curve = &mut self.bonding_curve;
+        bonding_curve.apply_quote(quote);
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        emit!(TradeEvent {
+            mint: self.mint.key(),
+            user: self.user.key(),
+            is_buy: false,
+            sol_amount: quote.sol_amount,
+            token_amount: quote.token_amount,
+            fee: fees.total,
+            creator_fee: fees.creator,
+            referral_fee: fees.referral,
+            virtual_sol_reserves: bonding_curve.virtual_sol_reserves,
+            virtual_token_reserves: bonding_curve.virtual_token_reserves,
+            real_sol_reserves: bonding_curve.real_sol_reserves,
+            real_token_reserves: bonding_curve.real_token_reserves,
+            timestamp,
+        });
+
         Ok(())
     }
 }
-
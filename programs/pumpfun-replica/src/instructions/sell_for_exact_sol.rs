@@ -0,0 +1,31 @@
+use crate::errors::*;
+use crate::instructions::sell::*;
+use anchor_lang::prelude::*;
+
+//Takes the same accounts as a sell, only the quote differs
+pub struct SellForExactSol;
+
+impl SellForExactSol {
+    pub fn handler(ctx: Context<Sell>, sol_amount: u64, max_tokens_in: u64) -> Result<()> {
+        //validate
+        ctx.accounts.validate(sol_amount)?;
+
+        //calculate tokens to be sold, the fee is added on top of the sol received
+        let quote = ctx
+            .accounts
+            .bonding_curve
+            .quote_sell_for_exact_sol(&ctx.accounts.global, sol_amount)
+            .ok_or(ContractError::CalculationError)?;
+
+        msg!("This is the token amount {}", quote.token_amount);
+
+        //Slippage Check
+        require!(
+            quote.token_amount <= max_tokens_in,
+            ContractError::TooManyTokensRequired
+        );
+
+        ctx.accounts
+            .execute_sell(&quote, ctx.bumps.bonding_curve_sol_escrow)
+    }
+}
@@ -0,0 +1,35 @@
+use crate::errors::*;
+use crate::states::{bonding_curve::*, global::*};
+use anchor_lang::prelude::*;
+
+use anchor_spl::token_interface::Mint;
+
+#[derive(Accounts)]
+pub struct SetHalted<'info> {
+    authority: Signer<'info>,
+
+    #[account(
+        seeds = [Global::SEED_PREFIX.as_bytes()],
+        constraint = global.initialized @ ContractError::NotInitialized,
+        constraint = global.global_authority == authority.key() @ ContractError::WrongAuthority,
+        bump,
+    )]
+    global: Box<Account<'info, Global>>,
+
+    mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [BondingCurve::SEED_PREFIX.as_bytes(), mint.to_account_info().key.as_ref()],
+        bump,
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+}
+
+impl SetHalted<'_> {
+    //Blocks buys and sells on a single curve
+    pub fn handler(ctx: Context<SetHalted>, halted: bool) -> Result<()> {
+        ctx.accounts.bonding_curve.halted = halted;
+        Ok(())
+    }
+}
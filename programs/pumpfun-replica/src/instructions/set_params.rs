@@ -0,0 +1,31 @@
+use crate::errors::*;
+use crate::states::global::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetParams<'info> {
+    authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Global::SEED_PREFIX.as_bytes()],
+        constraint = global.initialized @ ContractError::NotInitialized,
+        constraint = global.global_authority == authority.key() @ ContractError::WrongAuthority,
+        bump,
+    )]
+    global: Box<Account<'info, Global>>,
+}
+
+impl SetParams<'_> {
+    //Curves snapshot their initial reserves, supply, mint decimals, migrate_fee_amount,
+    //migration_token_allocation, graduation_sol_threshold and launch window at creation,
+    //so changes to those only apply to curves created after the update.
+    //fee_basis_points, creator_fee_basis_points, referral_fee_basis_points, lp_config and
+    //launch_bounds are read live and take effect on every curve immediately.
+    pub fn handler(ctx: Context<SetParams>, params: GlobalSettingsInput) -> Result<()> {
+        params.validate()?;
+
+        ctx.accounts.global.update_settings(params);
+        Ok(())
+    }
+}
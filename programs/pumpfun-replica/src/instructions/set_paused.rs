@@ -0,0 +1,25 @@
+use crate::errors::*;
+use crate::states::global::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Global::SEED_PREFIX.as_bytes()],
+        constraint = global.initialized @ ContractError::NotInitialized,
+        constraint = global.global_authority == authority.key() @ ContractError::WrongAuthority,
+        bump,
+    )]
+    global: Box<Account<'info, Global>>,
+}
+
+impl SetPaused<'_> {
+    //Blocks buys, sells and new curves everywhere, migration is left open
+    pub fn handler(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        ctx.accounts.global.paused = paused;
+        Ok(())
+    }
+}
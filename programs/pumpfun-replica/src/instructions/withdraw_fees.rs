@@ -0,0 +1,66 @@
+use crate::errors::*;
+use crate::events::*;
+use crate::states::{fee_vault::*, global::*};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    authority: Signer<'info>,
+
+    #[account(
+        seeds = [Global::SEED_PREFIX.as_bytes()],
+        constraint = global.initialized @ ContractError::NotInitialized,
+        constraint = global.global_authority == authority.key() @ ContractError::WrongAuthority,
+        bump,
+    )]
+    global: Box<Account<'info, Global>>,
+
+    #[account(
+        mut,
+        seeds = [FeeVault::SEED_PREFIX.as_bytes()],
+        bump = fee_vault.bump,
+    )]
+    fee_vault: Box<Account<'info, FeeVault>>,
+
+    #[account(mut)]
+    ///CHECK: Any account chosen by the global authority to receive the fees
+    destination: AccountInfo<'info>,
+}
+
+impl WithdrawFees<'_> {
+    pub fn validate(&self, amount: u64) -> Result<()> {
+        require!(amount > 0, ContractError::MinWithdraw);
+
+        //vault must stay rent exempt
+        let min_rent = Rent::get()?.minimum_balance(8 + FeeVault::INIT_SPACE);
+        let available = self.fee_vault.get_lamports().saturating_sub(min_rent);
+        require!(amount <= available, ContractError::InsufficientVaultFees);
+
+        Ok(())
+    }
+
+    pub fn handler(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+        //validate
+        ctx.accounts.validate(amount)?;
+
+        //vault is owned by this program so lamports are moved directly
+        ctx.accounts.fee_vault.sub_lamports(amount)?;
+        ctx.accounts.destination.add_lamports(amount)?;
+
+        ctx.accounts
+            .fee_vault
+            .record_withdrawal(amount)
+            .ok_or(ContractError::CalculationError)?;
+
+        emit!(WithdrawFeesEvent {
+            authority: ctx.accounts.authority.key(),
+            destination: ctx.accounts.destination.key(),
+            amount,
+            total_fees_collected: ctx.accounts.fee_vault.total_fees_collected,
+            total_fees_withdrawn: ctx.accounts.fee_vault.total_fees_withdrawn,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
@@ -3,8 +3,10 @@ use crate::states::*;
 use anchor_lang::prelude::*;
 
 pub mod errors;
+pub mod events;
 pub mod instructions;
 pub mod states;
+pub mod utils;
 
 declare_id!("32amtTMiGyJSm84RhinR8WbnUTU5BFMSo5FGFxQXKtJd");
 
@@ -16,18 +18,100 @@ pub mod pumpfun_replica {
         initialize::initialize(ctx, params)
     }
 
+    pub fn set_params(ctx: Context<SetParams>, params: GlobalSettingsInput) -> Result<()> {
+        SetParams::handler(ctx, params)
+    }
+
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        SetPaused::handler(ctx, paused)
+    }
+
+    pub fn set_halted(ctx: Context<SetHalted>, halted: bool) -> Result<()> {
+        SetHalted::handler(ctx, halted)
+    }
+
+    pub fn propose_authority(
+        ctx: Context<ProposeAuthority>,
+        role: AuthorityRole,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        ProposeAuthority::handler(ctx, role, new_authority)
+    }
+
+    pub fn accept_authority(ctx: Context<AcceptAuthority>, role: AuthorityRole) -> Result<()> {
+        AcceptAuthority::handler(ctx, role)
+    }
+
     pub fn create_bonding_curve(
         ctx: Context<CreateBondingCurve>,
         params: CreateBondingCurveParams,
     ) -> Result<()> {
         CreateBondingCurve::handler(ctx, params)
     }
-    pub fn buy(ctx: Context<Buy>, sol_amount: u64) -> Result<()> {
-        Buy::handler(ctx, sol_amount)
+
+    pub fn create_bonding_curve_with_mint(
+        ctx: Context<CreateBondingCurveWithMint>,
+        params: CreateBondingCurveParams,
+    ) -> Result<()> {
+        CreateBondingCurveWithMint::handler(ctx, params)
+    }
+    pub fn buy(ctx: Context<Buy>, sol_amount: u64, min_tokens_out: u64) -> Result<()> {
+        Buy::handler(ctx, sol_amount, min_tokens_out)
+    }
+
+    pub fn buy_exact_tokens(ctx: Context<Buy>, token_amount: u64, max_sol_cost: u64) -> Result<()> {
+        BuyExactTokens::handler(ctx, token_amount, max_sol_cost)
+    }
+
+    pub fn open_launch_buys(ctx: Context<OpenLaunchBuys>) -> Result<()> {
+        OpenLaunchBuys::handler(ctx)
+    }
+
+    pub fn close_launch_buys(ctx: Context<CloseLaunchBuys>) -> Result<()> {
+        CloseLaunchBuys::handler(ctx)
+    }
+
+    pub fn quote_buy(ctx: Context<QuoteBuy>, sol_amount: u64) -> Result<TradeQuote> {
+        QuoteBuy::handler(ctx, sol_amount)
+    }
+
+    pub fn quote_buy_exact_tokens(
+        ctx: Context<QuoteBuyExactTokens>,
+        token_amount: u64,
+    ) -> Result<TradeQuote> {
+        QuoteBuyExactTokens::handler(ctx, token_amount)
+    }
+
+    pub fn quote_sell(ctx: Context<QuoteSell>, token_amount: u64) -> Result<TradeQuote> {
+        QuoteSell::handler(ctx, token_amount)
+    }
+
+    pub fn sell(ctx: Context<Sell>, token_amount: u64, min_sol_output: u64) -> Result<()> {
+        Sell::handler(ctx, token_amount, min_sol_output)
+    }
+
+    pub fn sell_for_exact_sol(
+        ctx: Context<Sell>,
+        sol_amount: u64,
+        max_tokens_in: u64,
+    ) -> Result<()> {
+        SellForExactSol::handler(ctx, sol_amount, max_tokens_in)
+    }
+
+    pub fn migrate(ctx: Context<Migrate>) -> Result<()> {
+        Migrate::handler(ctx)
+    }
+
+    pub fn withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+        WithdrawFees::handler(ctx, amount)
+    }
+
+    pub fn claim_creator_fees(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+        ClaimCreatorFees::handler(ctx)
     }
 
-    pub fn sell(ctx: Context<Sell>, token_amount: u64) -> Result<()> {
-        Sell::handler(ctx, token_amount)
+    pub fn register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+        RegisterReferrer::handler(ctx)
     }
 
 }
@@ -1,4 +1,5 @@
-use crate::states::global::*;
+use crate::errors::*;
+use crate::states::{curve_pricing::*, global::*};
 use anchor_lang::prelude::*;
 
 #[account]
@@ -7,6 +8,13 @@ pub struct BondingCurve {
     pub mint: Pubkey,
     pub creator: Pubkey,
     pub initial_real_token_reserves: u64,
+    pub initial_virtual_sol_reserves: u64,
+    pub initial_virtual_token_reserves: u64,
+    pub curve_kind: CurveKind,
+    //migration terms at creation, later settings changes do not apply to this curve
+    pub migrate_fee_amount: u64,
+    pub migration_token_allocation: u64,
+    pub graduation_sol_threshold: u64,
 
     pub virtual_sol_reserves: u64,
     pub virtual_token_reserves: u64,
@@ -15,7 +23,16 @@ pub struct BondingCurve {
     pub real_token_reserves: u64,
 
     pub token_total_supply: u64,
+    pub mint_decimals: u8,
     pub complete: bool,
+    pub migrated: bool,
+    //stops trading on this curve only
+    pub halted: bool,
+    //unix timestamp of creation, opens the launch window
+    pub created_at: i64,
+    //launch window terms at creation, buys before the end are capped per wallet
+    pub launch_window_ends_at: i64,
+    pub launch_window_max_tokens: u64,
     pub bump: u8,
 }
 
@@ -24,106 +41,244 @@ pub struct CreateBondingCurveParams {
     pub name: String,
     pub symbol: String,
     pub uri: String,
+    //pricing shape, constant product when unset
+    pub curve_kind: Option<CurveKind>,
+    //launch values picked by the creator, the global default is used when unset
+    pub initial_virtual_sol_reserves: Option<u64>,
+    pub initial_virtual_token_reserves: Option<u64>,
+    pub initial_real_token_reserves: Option<u64>,
+    pub token_total_supply: Option<u64>,
+}
+
+//Outcome of a buy or sell against the current curve, returned by the quote
+//instructions and applied by the trade handlers. `sol_amount` is the curve leg
+//before fees, paid on top of it for buys and deducted from it for sells.
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TradeQuote {
+    pub token_amount: u64,
+    pub sol_amount: u64,
+    pub fee: u64,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub complete: bool,
 }
 
 impl BondingCurve {
     pub const SEED_PREFIX: &'static str = "bonding-curve";
     pub const SOL_ESCROW_SEED_PREFIX: &'static str = "sol-escrow";
+
     pub fn update_from_params(
         &mut self,
         mint: Pubkey,
         creator: Pubkey,
+        params: &CreateBondingCurveParams,
         global: &Global,
         bump: u8,
-    ) -> &mut Self {
+    ) -> Result<&mut Self> {
+        let initial_virtual_sol_reserves = params
+            .initial_virtual_sol_reserves
+            .unwrap_or(global.initial_virtual_sol_reserves);
+        let initial_virtual_token_reserves = params
+            .initial_virtual_token_reserves
+            .unwrap_or(global.initial_virtual_token_reserves);
+        let initial_real_token_reserves = params
+            .initial_real_token_reserves
+            .unwrap_or(global.initial_real_token_reserves);
+        let token_total_supply = params
+            .token_total_supply
+            .unwrap_or(global.token_total_supply);
+
+        require!(
+            global.launch_bounds.contains(
+                initial_virtual_sol_reserves,
+                initial_virtual_token_reserves,
+                initial_real_token_reserves,
+                token_total_supply,
+            ),
+            ContractError::LaunchParamsOutOfBounds
+        );
+        validate_launch_reserves(
+            initial_virtual_sol_reserves,
+            initial_virtual_token_reserves,
+            initial_real_token_reserves,
+            token_total_supply,
+            global.migration_token_allocation,
+        )
+        .map_err(|_| ContractError::InvalidLaunchParams)?;
+
+        let created_at = Clock::get()?.unix_timestamp;
         self.clone_from(&BondingCurve {
             mint,
             creator,
-            initial_real_token_reserves: global.initial_real_token_reserves,
-            virtual_sol_reserves: global.initial_virtual_sol_reserves,
-            virtual_token_reserves: global.initial_virtual_token_reserves,
+            initial_real_token_reserves,
+            initial_virtual_sol_reserves,
+            initial_virtual_token_reserves,
+            curve_kind: params.curve_kind.unwrap_or_default(),
+            migrate_fee_amount: global.migrate_fee_amount,
+            migration_token_allocation: global.migration_token_allocation,
+            graduation_sol_threshold: global.graduation_sol_threshold,
+            virtual_sol_reserves: initial_virtual_sol_reserves,
+            virtual_token_reserves: initial_virtual_token_reserves,
             real_sol_reserves: 0,
-            real_token_reserves: global.initial_real_token_reserves,
-            token_total_supply: global.token_total_supply,
+            real_token_reserves: initial_real_token_reserves,
+            token_total_supply,
+            mint_decimals: global.mint_decimals,
             complete: false,
+            migrated: false,
+            halted: false,
+            created_at,
+            launch_window_ends_at: created_at.saturating_add(global.launch_window_seconds),
+            launch_window_max_tokens: global.launch_window_max_tokens,
             bump,
         });
-        self
+        Ok(self)
     }
 
     //TOKENS TO BE RECEIVED FOR DEPOSITING A PARTICULAR AMOUNT OF SOL
     pub fn get_tokens_for_buy_with_sol(&self, sol_amount: u64) -> Option<u64> {
-        if sol_amount == 0 {
-            return None;
-        }
+        self.curve_kind.pricing().tokens_for_buy(self, sol_amount)
+    }
+
+    //SOL TO BE DEPOSITED FOR RECEIVING A PARTICULAR AMOUNT OF TOKENS
+    pub fn get_sol_for_buy_of_tokens(&self, token_amount: u64) -> Option<u64> {
+        self.curve_kind.pricing().sol_for_buy(self, token_amount)
+    }
+
+    //SOL TO BE RECEIVED FOR SELLING A PARTICULAR AMOUNT OF TOKENS
+    pub fn get_sol_for_sale_on_tokens(&self, token_amount: u64) -> Option<u64> {
+        self.curve_kind.pricing().sol_for_sale(self, token_amount)
+    }
 
-        //convert to common decimal basis(using 9 decimals as base)
-        let current_sol = self.virtual_sol_reserves as u128;
-        let current_tokens = (self.virtual_token_reserves as u128)
-            .checked_mul(1_000_000_000)?
-            .checked_div(1_000_000)?;
-        //calculate new reserves using the constant product formula
-        let new_sol = current_sol.checked_add(sol_amount as u128)?;
-        let new_tokens = (current_sol.checked_mul(current_tokens)?).checked_div(new_sol)?;
+    //TOKENS TO BE DEPOSITED FOR RECEIVING A PARTICULAR AMOUNT OF SOL
+    pub fn get_tokens_for_sale_of_sol(&self, sol_amount: u64) -> Option<u64> {
+        self.curve_kind.pricing().tokens_for_sale(self, sol_amount)
+    }
 
-        let tokens_out = current_tokens.checked_sub(new_tokens)?;
+    //TOKENS BOUGHT OUT OF THE CURVE INVENTORY SO FAR
+    pub fn tokens_sold(&self) -> Option<u64> {
+        self.initial_real_token_reserves
+            .checked_sub(self.real_token_reserves)
+    }
 
-        //Convert back to 6 decimal places for tokens
-        let tokens_out = tokens_out
-            .checked_mul(1_000_000)?
-            .checked_div(1_000_000_000)?;
+    //SOL NEEDED TO TAKE OUT ALL REMAINING REAL TOKEN RESERVES AT THE CURRENT PRICE
+    pub fn recompute_sol_amount_for_last_buy(&self) -> Option<u64> {
+        self.get_sol_for_buy_of_tokens(self.real_token_reserves)
+    }
 
-        //Return Tokens
-        <u128 as TryInto<u64>>::try_into(tokens_out).ok()
+    //WHETHER `now` FALLS IN THE WINDOW AFTER CREATION WHERE PER WALLET BUYS ARE CAPPED,
+    //THE WINDOW IS EMPTY WHEN THE CURVE WAS CREATED WITHOUT ONE
+    pub fn in_launch_window(&self, now: i64) -> bool {
+        now < self.launch_window_ends_at
     }
 
-    pub fn get_sol_for_sale_on_tokens(&self, token_amount: u64) -> Option<u64> {
-        if token_amount == 0 {
+    //SOL STILL TO BE RAISED BEFORE THE CURVE GRADUATES, NONE WHEN THERE IS NO SOL THRESHOLD.
+    //BUYS STOP AT THE CURVE'S OWN THRESHOLD, SO ITS RESERVES NEVER RUN PAST IT
+    pub fn sol_to_graduate(&self) -> Option<u64> {
+        if self.graduation_sol_threshold == 0 {
             return None;
         }
+        Some(
+            self.graduation_sol_threshold
+                .saturating_sub(self.real_sol_reserves),
+        )
+    }
+
+    //BUY WITH `sol_amount`, THE LAST BUY IS CLAMPED TO THE SOL LEFT TO GRADUATE OR THE REMAINING TOKENS
+    pub fn quote_buy(&self, global: &Global, sol_amount: u64) -> Option<TradeQuote> {
+        self.quote_clamped_buy(global, sol_amount, None)
+    }
 
-        // Convert to common decimal basis (using 9 decimals as base)
-        let current_sol = self.virtual_sol_reserves as u128;
-        let current_tokens = (self.virtual_token_reserves as u128)
-            .checked_mul(1_000_000_000)? // Scale tokens up to 9 decimals
-            .checked_div(1_000_000)?; // From 6 decimals
+    //BUY OF EXACTLY `token_amount`, CLAMPED THE SAME WAY AS `quote_buy`
+    pub fn quote_buy_exact_tokens(&self, global: &Global, token_amount: u64) -> Option<TradeQuote> {
+        let sol_amount =
+            self.get_sol_for_buy_of_tokens(token_amount.min(self.real_token_reserves))?;
+        self.quote_clamped_buy(global, sol_amount, Some(token_amount))
+    }
 
-        // Calculate new reserves using constant product formula
-        let new_tokens = current_tokens.checked_add(
-            (token_amount as u128)
-                .checked_mul(1_000_000_000)? // Scale input tokens to 9 decimals
-                .checked_div(1_000_000)?, // From 6 decimals
-        )?;
+    //CLAMPS A BUY TO THE SOL LEFT TO GRADUATE FIRST, THEN TO THE REMAINING TOKENS.
+    //`token_amount` IS PRICED FROM `sol_amount` WHEN NOT GIVEN
+    fn quote_clamped_buy(
+        &self,
+        global: &Global,
+        sol_amount: u64,
+        token_amount: Option<u64>,
+    ) -> Option<TradeQuote> {
+        let mut sol_amount = sol_amount;
+        let mut token_amount = token_amount;
+        let mut complete = false;
+        if let Some(remaining_sol) = self.sol_to_graduate() {
+            //a curve holding its threshold has graduated, there is nothing left to buy
+            if remaining_sol == 0 {
+                return None;
+            }
+            if sol_amount >= remaining_sol {
+                //only the SOL still needed is raised and its tokens are sold
+                sol_amount = remaining_sol;
+                token_amount = None;
+                complete = true;
+            }
+        }
 
-        let new_sol = (current_sol.checked_mul(current_tokens)?).checked_div(new_tokens)?;
+        let mut token_amount = match token_amount {
+            Some(token_amount) => token_amount,
+            None => self.get_tokens_for_buy_with_sol(sol_amount)?,
+        };
+        if token_amount >= self.real_token_reserves {
+            complete = true;
+            //only the remaining reserves are sold and only their cost is charged
+            token_amount = self.real_token_reserves;
+            sol_amount = self.recompute_sol_amount_for_last_buy()?.min(sol_amount);
+        }
 
-        let sol_out = current_sol.checked_sub(new_sol)?;
+        let fee = global.get_fee(sol_amount)?;
 
-        msg!("GetSolForSellTokens: sol_out: {}", sol_out);
-        <u128 as TryInto<u64>>::try_into(sol_out).ok()
+        let mut curve_after = self.clone();
+        curve_after.update_reserves_after_buy(token_amount, sol_amount)?;
+        Some(curve_after.quote(token_amount, sol_amount, fee, complete))
     }
 
-    pub fn recompute_sol_amount_for_last_buy(&mut self) -> Option<u64> {
-        let token_amount = self.real_token_reserves;
+    //SELL OF `token_amount`, THE FEE IS TAKEN OUT OF THE SOL PROCEEDS
+    pub fn quote_sell(&self, global: &Global, token_amount: u64) -> Option<TradeQuote> {
+        let sol_amount = self.get_sol_for_sale_on_tokens(token_amount)?;
+        let fee = global.get_fee(sol_amount)?;
 
-        //Temporarily store current state
-        let current_virtual_token_reserves = self.virtual_token_reserves;
-        let current_virtual_sol_reserves = self.virtual_sol_reserves;
+        let mut curve_after = self.clone();
+        curve_after.update_reserves_after_sell(token_amount, sol_amount)?;
+        Some(curve_after.quote(token_amount, sol_amount, fee, false))
+    }
 
-        //Update self with new amount for the temporary calculation with constant product formula
-        self.virtual_token_reserves = (current_virtual_token_reserves as u128)
-            .checked_sub(token_amount as u128)?
-            .try_into()
-            .ok()?;
-        self.virtual_sol_reserves = 115_005_359_056;
+    //SELL FOR EXACTLY `sol_amount` AFTER FEES, THE FEE IS GROSSED UP ON TOP OF IT
+    pub fn quote_sell_for_exact_sol(&self, global: &Global, sol_amount: u64) -> Option<TradeQuote> {
+        let sell_amount_plus_fee = global.get_sol_amount_before_fee(sol_amount)?;
+        let fee = sell_amount_plus_fee.checked_sub(sol_amount)?;
+        let token_amount = self.get_tokens_for_sale_of_sol(sell_amount_plus_fee)?;
 
-        let recomputed_sol_amount = self.get_sol_for_sale_on_tokens(token_amount);
+        let mut curve_after = self.clone();
+        curve_after.update_reserves_after_sell(token_amount, sell_amount_plus_fee)?;
+        Some(curve_after.quote(token_amount, sell_amount_plus_fee, fee, false))
+    }
 
-        // Restore the state with the recomputed sol_amount
-        self.virtual_token_reserves = current_virtual_token_reserves;
-        self.virtual_sol_reserves = current_virtual_sol_reserves;
+    fn quote(&self, token_amount: u64, sol_amount: u64, fee: u64, complete: bool) -> TradeQuote {
+        TradeQuote {
+            token_amount,
+            sol_amount,
+            fee,
+            virtual_sol_reserves: self.virtual_sol_reserves,
+            virtual_token_reserves: self.virtual_token_reserves,
+            real_sol_reserves: self.real_sol_reserves,
+            real_token_reserves: self.real_token_reserves,
+            complete,
+        }
+    }
 
-        recomputed_sol_amount
+    pub fn apply_quote(&mut self, quote: &TradeQuote) {
+        self.virtual_sol_reserves = quote.virtual_sol_reserves;
+        self.virtual_token_reserves = quote.virtual_token_reserves;
+        self.real_sol_reserves = quote.real_sol_reserves;
+        self.real_token_reserves = quote.real_token_reserves;
+        self.complete = self.complete || quote.complete;
     }
 
     pub fn update_reserves_after_buy(&mut self, token_amount: u64, sol_amount: u64) -> Option<()> {
@@ -204,7 +359,7 @@ pub fn update_reserves_after_sell(&mut self, token_amount:u64, sol_amount: u64)
     self.virtual_token_reserves = new_virtual_token_reserves.try_into().ok()?;
     self.real_token_reserves = new_real_token_reserves.try_into().ok()?;
     self.virtual_sol_reserves = new_virtual_sol_reserves.try_into().ok()?;
-    self.real_sol_reserves = new_real_sol_reserves.try_into().ok()?;
+    self.real_sol_reserves = new_real_sol_reserves;
 
     Some(())
     
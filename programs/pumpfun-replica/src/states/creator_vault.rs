@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct CreatorVault {
+    pub creator: Pubkey,
+    //lifetime totals across all of the creator's curves
+    pub total_fees_accrued: u64,
+    pub total_fees_claimed: u64,
+    pub bump: u8,
+}
+
+impl CreatorVault {
+    pub const SEED_PREFIX: &'static str = "creator-vault";
+
+    pub fn record_fee(&mut self, fee_lamports: u64) -> Option<()> {
+        self.total_fees_accrued = self.total_fees_accrued.checked_add(fee_lamports)?;
+        Some(())
+    }
+
+    pub fn record_claim(&mut self, amount: u64) -> Option<()> {
+        self.total_fees_claimed = self.total_fees_claimed.checked_add(amount)?;
+        Some(())
+    }
+}
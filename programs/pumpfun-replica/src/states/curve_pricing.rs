@@ -0,0 +1,420 @@
+use crate::states::bonding_curve::*;
+use anchor_lang::prelude::*;
+
+#[derive(
+    AnchorSerialize, AnchorDeserialize, InitSpace, Debug, Clone, Copy, PartialEq, Eq, Default,
+)]
+pub enum CurveKind {
+    //x*y=k over the virtual reserves
+    #[default]
+    ConstantProduct,
+    //price rises in a straight line with the tokens sold
+    Linear,
+    //price compounds from one tier to the next
+    Exponential,
+    //price rises by the launch price from one tier to the next
+    Stepped,
+}
+
+impl CurveKind {
+    pub fn pricing(&self) -> &'static dyn CurvePricing {
+        match self {
+            CurveKind::ConstantProduct => &ConstantProduct,
+            CurveKind::Linear => &Linear,
+            CurveKind::Exponential => &Exponential,
+            CurveKind::Stepped => &Stepped,
+        }
+    }
+}
+
+//Buy and sell dispatch through this trait. Costs round up and proceeds round
+//down so the escrow always covers what the curve owes.
+pub trait CurvePricing {
+    //SOL TO BE DEPOSITED FOR RECEIVING `token_amount`
+    fn sol_for_buy(&self, curve: &BondingCurve, token_amount: u64) -> Option<u64>;
+
+    //SOL TO BE RECEIVED FOR SELLING `token_amount`
+    fn sol_for_sale(&self, curve: &BondingCurve, token_amount: u64) -> Option<u64>;
+
+    //TOKENS TO BE RECEIVED FOR DEPOSITING `sol_amount`
+    fn tokens_for_buy(&self, curve: &BondingCurve, sol_amount: u64) -> Option<u64> {
+        //largest amount whose cost fits, the cost grows with the amount
+        let (mut low, mut high) = (0u64, u64::MAX);
+        while low < high {
+            let mid = low + (high - low) / 2 + 1;
+            match self.sol_for_buy(curve, mid) {
+                Some(cost) if cost <= sol_amount => low = mid,
+                _ => high = mid - 1,
+            }
+        }
+        Some(low)
+    }
+
+    //TOKENS TO BE DEPOSITED FOR RECEIVING `sol_amount`
+    fn tokens_for_sale(&self, curve: &BondingCurve, sol_amount: u64) -> Option<u64> {
+        let sold = curve.tokens_sold()?;
+        if sol_amount == 0 || self.sol_for_sale(curve, sold)? < sol_amount {
+            return None;
+        }
+
+        //smallest amount whose proceeds cover `sol_amount`
+        let (mut low, mut high) = (1u64, sold);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.sol_for_sale(curve, mid)? >= sol_amount {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        Some(low)
+    }
+}
+
+//Fixed point denominator for per token prices, tokens are in mint base units
+pub const PRICE_PRECISION: u128 = 1_000_000_000_000_000_000;
+
+//LAUNCH PRICE OF ONE TOKEN BASE UNIT, THE SAME STARTING PRICE AS THE CONSTANT PRODUCT CURVE
+fn start_price(curve: &BondingCurve) -> Option<u128> {
+    (curve.initial_virtual_sol_reserves as u128)
+        .checked_mul(PRICE_PRECISION)?
+        .checked_div(curve.initial_virtual_token_reserves as u128)
+}
+
+fn div(numerator: u128, denominator: u128, round_up: bool) -> Option<u128> {
+    if round_up {
+        numerator
+            .checked_add(denominator.checked_sub(1)?)?
+            .checked_div(denominator)
+    } else {
+        numerator.checked_div(denominator)
+    }
+}
+
+pub struct ConstantProduct;
+
+impl CurvePricing for ConstantProduct {
+    //TOKENS TO BE RECEIVED FOR DEPOSITING A PARTICULAR AMOUNT OF SOL
+    fn tokens_for_buy(&self, curve: &BondingCurve, sol_amount: u64) -> Option<u64> {
+        if sol_amount == 0 {
+            return None;
+        }
+
+        //reserves and amounts stay in their own base units, the product does not
+        //depend on the mint decimals
+        let current_sol = curve.virtual_sol_reserves as u128;
+        let current_tokens = curve.virtual_token_reserves as u128;
+        //calculate new reserves using the constant product formula,
+        //rounded up so the buyer never receives more than the curve price
+        let new_sol = current_sol.checked_add(sol_amount as u128)?;
+        let new_tokens = div(current_sol.checked_mul(current_tokens)?, new_sol, true)?;
+
+        let tokens_out = current_tokens.checked_sub(new_tokens)?;
+
+        //Return Tokens
+        <u128 as TryInto<u64>>::try_into(tokens_out).ok()
+    }
+
+    //SOL TO BE DEPOSITED FOR RECEIVING A PARTICULAR AMOUNT OF TOKENS
+    fn sol_for_buy(&self, curve: &BondingCurve, token_amount: u64) -> Option<u64> {
+        if token_amount == 0 {
+            return None;
+        }
+
+        let current_sol = curve.virtual_sol_reserves as u128;
+        let current_tokens = curve.virtual_token_reserves as u128;
+
+        //calculate new reserves using the constant product formula
+        let new_tokens = current_tokens.checked_sub(token_amount as u128)?;
+        if new_tokens == 0 {
+            return None;
+        }
+
+        //Round up so the protocol never receives less than the curve price
+        let new_sol = current_sol
+            .checked_mul(current_tokens)?
+            .checked_add(new_tokens.checked_sub(1)?)?
+            .checked_div(new_tokens)?;
+
+        let sol_in = new_sol.checked_sub(current_sol)?;
+
+        //Return Sol
+        <u128 as TryInto<u64>>::try_into(sol_in).ok()
+    }
+
+    fn sol_for_sale(&self, curve: &BondingCurve, token_amount: u64) -> Option<u64> {
+        if token_amount == 0 {
+            return None;
+        }
+
+        let current_sol = curve.virtual_sol_reserves as u128;
+        let current_tokens = curve.virtual_token_reserves as u128;
+
+        // Calculate new reserves using constant product formula
+        let new_tokens = current_tokens.checked_add(token_amount as u128)?;
+
+        let new_sol = (current_sol.checked_mul(current_tokens)?).checked_div(new_tokens)?;
+
+        let sol_out = current_sol.checked_sub(new_sol)?;
+
+        msg!("GetSolForSellTokens: sol_out: {}", sol_out);
+        <u128 as TryInto<u64>>::try_into(sol_out).ok()
+    }
+
+    //TOKENS TO BE DEPOSITED FOR RECEIVING A PARTICULAR AMOUNT OF SOL
+    fn tokens_for_sale(&self, curve: &BondingCurve, sol_amount: u64) -> Option<u64> {
+        if sol_amount == 0 {
+            return None;
+        }
+
+        let current_sol = curve.virtual_sol_reserves as u128;
+        let current_tokens = curve.virtual_token_reserves as u128;
+
+        // Calculate new reserves using constant product formula
+        let new_sol = current_sol.checked_sub(sol_amount as u128)?;
+        if new_sol == 0 {
+            return None;
+        }
+
+        // Round up so the protocol never pays out more than the curve price
+        let new_tokens = current_sol
+            .checked_mul(current_tokens)?
+            .checked_add(new_sol.checked_sub(1)?)?
+            .checked_div(new_sol)?;
+
+        let tokens_in = new_tokens.checked_sub(current_tokens)?;
+
+        <u128 as TryInto<u64>>::try_into(tokens_in).ok()
+    }
+}
+
+pub struct Linear;
+
+impl Linear {
+    //price once the curve inventory is sold, as a multiple of the launch price
+    pub const END_PRICE_MULTIPLE: u128 = 8;
+
+    //AREA UNDER p(s) = P0 * (1 + k * s / R) FROM `sold` TO `sold + amount`,
+    //WHICH IS P0 * amount * (2R + k * (2 * sold + amount)) / 2R
+    fn cost(curve: &BondingCurve, sold: u64, amount: u64, round_up: bool) -> Option<u64> {
+        let double_range = (curve.initial_real_token_reserves as u128).checked_mul(2)?;
+        let slope = Self::END_PRICE_MULTIPLE - 1;
+        let rise =
+            slope.checked_mul((sold as u128).checked_mul(2)?.checked_add(amount as u128)?)?;
+        let area = (amount as u128).checked_mul(double_range.checked_add(rise)?)?;
+
+        let units = div(area, double_range, round_up)?;
+        let sol = div(
+            units.checked_mul(start_price(curve)?)?,
+            PRICE_PRECISION,
+            round_up,
+        )?;
+        sol.try_into().ok()
+    }
+}
+
+impl CurvePricing for Linear {
+    fn sol_for_buy(&self, curve: &BondingCurve, token_amount: u64) -> Option<u64> {
+        Self::cost(curve, curve.tokens_sold()?, token_amount, true)
+    }
+
+    fn sol_for_sale(&self, curve: &BondingCurve, token_amount: u64) -> Option<u64> {
+        let sold = curve.tokens_sold()?.checked_sub(token_amount)?;
+        Self::cost(curve, sold, token_amount, false)
+    }
+}
+
+//Piecewise constant price over `COUNT` equal slices of the curve inventory,
+//tokens past the last tier keep its price
+pub trait Tiers {
+    const COUNT: u64;
+
+    //PRICE OF THE TIER FOLLOWING ONE PRICED AT `price`
+    fn next_price(price: u128, start_price: u128) -> Option<u128>;
+}
+
+pub struct Exponential;
+
+impl Exponential {
+    //2^(1/5) per tier, 15 steps up to the last tier put it at ~8x the launch price
+    pub const GROWTH_BASIS_POINTS: u128 = 11_487;
+}
+
+impl Tiers for Exponential {
+    const COUNT: u64 = 16;
+
+    fn next_price(price: u128, _start_price: u128) -> Option<u128> {
+        price
+            .checked_mul(Self::GROWTH_BASIS_POINTS)?
+            .checked_div(10_000)
+    }
+}
+
+impl CurvePricing for Exponential {
+    fn sol_for_buy(&self, curve: &BondingCurve, token_amount: u64) -> Option<u64> {
+        tiered_sol_for_buy::<Self>(curve, token_amount)
+    }
+
+    fn sol_for_sale(&self, curve: &BondingCurve, token_amount: u64) -> Option<u64> {
+        tiered_sol_for_sale::<Self>(curve, token_amount)
+    }
+
+    fn tokens_for_buy(&self, curve: &BondingCurve, sol_amount: u64) -> Option<u64> {
+        tiered_tokens_for_buy::<Self>(curve, sol_amount)
+    }
+
+    fn tokens_for_sale(&self, curve: &BondingCurve, sol_amount: u64) -> Option<u64> {
+        tiered_tokens_for_sale::<Self>(curve, sol_amount)
+    }
+}
+
+pub struct Stepped;
+
+impl Tiers for Stepped {
+    //the last tier trades at 8x the launch price
+    const COUNT: u64 = 8;
+
+    fn next_price(price: u128, start_price: u128) -> Option<u128> {
+        price.checked_add(start_price)
+    }
+}
+
+impl CurvePricing for Stepped {
+    fn sol_for_buy(&self, curve: &BondingCurve, token_amount: u64) -> Option<u64> {
+        tiered_sol_for_buy::<Self>(curve, token_amount)
+    }
+
+    fn sol_for_sale(&self, curve: &BondingCurve, token_amount: u64) -> Option<u64> {
+        tiered_sol_for_sale::<Self>(curve, token_amount)
+    }
+
+    fn tokens_for_buy(&self, curve: &BondingCurve, sol_amount: u64) -> Option<u64> {
+        tiered_tokens_for_buy::<Self>(curve, sol_amount)
+    }
+
+    fn tokens_for_sale(&self, curve: &BondingCurve, sol_amount: u64) -> Option<u64> {
+        tiered_tokens_for_sale::<Self>(curve, sol_amount)
+    }
+}
+
+fn tier_width<T: Tiers>(curve: &BondingCurve) -> u128 {
+    (curve.initial_real_token_reserves / T::COUNT).max(1) as u128
+}
+
+fn tier_of<T: Tiers>(position: u128, width: u128) -> u64 {
+    (position / width).min((T::COUNT - 1) as u128) as u64
+}
+
+fn tier_start(tier: u64, width: u128) -> u128 {
+    tier as u128 * width
+}
+
+fn tier_end<T: Tiers>(tier: u64, width: u128) -> u128 {
+    if tier == T::COUNT - 1 {
+        u128::MAX
+    } else {
+        (tier as u128 + 1) * width
+    }
+}
+
+fn tier_price<T: Tiers>(start_price: u128, tier: u64) -> Option<u128> {
+    let mut price = start_price;
+    for _ in 0..tier {
+        price = T::next_price(price, start_price)?;
+    }
+    Some(price)
+}
+
+//PRICE TIMES AMOUNT OVER TOKEN POSITIONS [from, to), FIXED POINT OVER PRICE_PRECISION
+fn tiered_area<T: Tiers>(curve: &BondingCurve, from: u128, to: u128) -> Option<u128> {
+    let width = tier_width::<T>(curve);
+    let start = start_price(curve)?;
+    let mut tier = tier_of::<T>(from, width);
+    let mut price = tier_price::<T>(start, tier)?;
+
+    let mut position = from;
+    let mut area = 0u128;
+    while position < to {
+        let end = tier_end::<T>(tier, width).min(to);
+        area = area.checked_add((end - position).checked_mul(price)?)?;
+        position = end;
+        if position < to {
+            tier += 1;
+            price = T::next_price(price, start)?;
+        }
+    }
+    Some(area)
+}
+
+fn tiered_sol_for_buy<T: Tiers>(curve: &BondingCurve, token_amount: u64) -> Option<u64> {
+    let sold = curve.tokens_sold()? as u128;
+    let area = tiered_area::<T>(curve, sold, sold.checked_add(token_amount as u128)?)?;
+    div(area, PRICE_PRECISION, true)?.try_into().ok()
+}
+
+fn tiered_sol_for_sale<T: Tiers>(curve: &BondingCurve, token_amount: u64) -> Option<u64> {
+    let sold = curve.tokens_sold()? as u128;
+    let area = tiered_area::<T>(curve, sold.checked_sub(token_amount as u128)?, sold)?;
+    div(area, PRICE_PRECISION, false)?.try_into().ok()
+}
+
+//WALKS UP THE TIERS SPENDING `sol_amount`, KEEPING THE COST WITHIN IT
+fn tiered_tokens_for_buy<T: Tiers>(curve: &BondingCurve, sol_amount: u64) -> Option<u64> {
+    let width = tier_width::<T>(curve);
+    let start = start_price(curve)?;
+    let sold = curve.tokens_sold()? as u128;
+    let mut tier = tier_of::<T>(sold, width);
+    let mut price = tier_price::<T>(start, tier)?;
+
+    let mut budget = (sol_amount as u128).checked_mul(PRICE_PRECISION)?;
+    let mut position = sold;
+    loop {
+        let capacity = tier_end::<T>(tier, width) - position;
+        match capacity.checked_mul(price) {
+            Some(tier_cost) if tier_cost <= budget => {
+                budget -= tier_cost;
+                position += capacity;
+                tier += 1;
+                price = T::next_price(price, start)?;
+            }
+            _ => {
+                position = position.checked_add(budget.checked_div(price)?)?;
+                break;
+            }
+        }
+    }
+    (position - sold).try_into().ok()
+}
+
+//WALKS DOWN THE TIERS UNTIL THE PROCEEDS COVER `sol_amount`
+fn tiered_tokens_for_sale<T: Tiers>(curve: &BondingCurve, sol_amount: u64) -> Option<u64> {
+    let sold = curve.tokens_sold()? as u128;
+    if sol_amount == 0 || sold == 0 {
+        return None;
+    }
+
+    let width = tier_width::<T>(curve);
+    let start = start_price(curve)?;
+    let mut tier = tier_of::<T>(sold - 1, width);
+    let mut price = tier_price::<T>(start, tier)?;
+
+    let mut needed = (sol_amount as u128).checked_mul(PRICE_PRECISION)?;
+    let mut position = sold;
+    loop {
+        let capacity = position - tier_start(tier, width);
+        let tier_proceeds = capacity.checked_mul(price)?;
+        if tier_proceeds >= needed {
+            position = position.checked_sub(div(needed, price, true)?)?;
+            break;
+        }
+        //nothing left below the first tier to cover the rest
+        if tier == 0 {
+            return None;
+        }
+        needed -= tier_proceeds;
+        position -= capacity;
+        tier -= 1;
+        price = tier_price::<T>(start, tier)?;
+    }
+    (sold - position).try_into().ok()
+}
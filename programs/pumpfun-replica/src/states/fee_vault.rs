@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct FeeVault {
+    //lifetime totals, the withdrawable balance is the vault lamports above rent
+    pub total_fees_collected: u64,
+    pub total_fees_withdrawn: u64,
+    pub bump: u8,
+}
+
+impl FeeVault {
+    pub const SEED_PREFIX: &'static str = "fee-vault";
+
+    pub fn record_fee(&mut self, fee_lamports: u64) -> Option<()> {
+        self.total_fees_collected = self.total_fees_collected.checked_add(fee_lamports)?;
+        Some(())
+    }
+
+    pub fn record_withdrawal(&mut self, amount: u64) -> Option<()> {
+        self.total_fees_withdrawn = self.total_fees_withdrawn.checked_add(amount)?;
+        Some(())
+    }
+}
@@ -1,14 +1,26 @@
+use crate::errors::*;
 use anchor_lang::prelude::*;
 
 #[account]
 #[derive(InitSpace)]
 pub struct Global {
     pub initialized: bool,
+    //stops trading and launches on every curve, migrations can still finish
+    pub paused: bool,
     pub global_authority: Pubkey,
     pub migration_authority: Pubkey,
     pub migrate_fee_amount: u64,
     pub migration_token_allocation: u64,
-    pub fee_receiver: Pubkey,
+    //real SOL raised at which a curve completes, 0 completes only when its token inventory runs out
+    pub graduation_sol_threshold: u64,
+    //seconds after creation during which each wallet's buys on a curve are capped, 0 disables the window
+    pub launch_window_seconds: i64,
+    pub launch_window_max_tokens: u64,
+    pub fee_basis_points: u64,
+    //share of each trade fee paid to the curve creator, in basis points of the fee
+    pub creator_fee_basis_points: u64,
+    //share of the protocol fee paid to the referrer of a trade, in basis points of that fee
+    pub referral_fee_basis_points: u64,
 
     pub initial_virtual_token_reserves: u64,
     pub initial_virtual_sol_reserves: u64,
@@ -17,6 +29,19 @@ pub struct Global {
     pub mint_decimals: u8,
 
     pub lp_config: Pubkey,
+
+    //limits on the launch values creators may pick per curve
+    pub launch_bounds: LaunchBounds,
+
+    //set by the current authority, rotated once the new key accepts
+    pub pending_global_authority: Pubkey,
+    pub pending_migration_authority: Pubkey,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthorityRole {
+    Global,
+    Migration,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
@@ -28,12 +53,151 @@ pub struct GlobalSettingsInput {
     pub mint_decimals: u8,
     pub migrate_fee_amount: u64,
     pub migration_token_allocation: u64,
-    pub fee_receiver: Pubkey,
+    pub graduation_sol_threshold: u64,
+    pub launch_window_seconds: i64,
+    pub launch_window_max_tokens: u64,
+    pub fee_basis_points: u64,
+    pub creator_fee_basis_points: u64,
+    pub referral_fee_basis_points: u64,
     pub lp_config: Pubkey,
+    pub launch_bounds: LaunchBounds,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LaunchBounds {
+    pub min_virtual_sol_reserves: u64,
+    pub max_virtual_sol_reserves: u64,
+    pub min_virtual_token_reserves: u64,
+    pub max_virtual_token_reserves: u64,
+    pub min_real_token_reserves: u64,
+    pub max_real_token_reserves: u64,
+    pub min_token_total_supply: u64,
+    pub max_token_total_supply: u64,
+}
+
+impl LaunchBounds {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.min_virtual_sol_reserves <= self.max_virtual_sol_reserves
+                && self.min_virtual_token_reserves <= self.max_virtual_token_reserves
+                && self.min_real_token_reserves <= self.max_real_token_reserves
+                && self.min_token_total_supply <= self.max_token_total_supply,
+            ContractError::InvalidGlobalSettings
+        );
+        Ok(())
+    }
+
+    pub fn contains(
+        &self,
+        virtual_sol_reserves: u64,
+        virtual_token_reserves: u64,
+        real_token_reserves: u64,
+        token_total_supply: u64,
+    ) -> bool {
+        (self.min_virtual_sol_reserves..=self.max_virtual_sol_reserves)
+            .contains(&virtual_sol_reserves)
+            && (self.min_virtual_token_reserves..=self.max_virtual_token_reserves)
+                .contains(&virtual_token_reserves)
+            && (self.min_real_token_reserves..=self.max_real_token_reserves)
+                .contains(&real_token_reserves)
+            && (self.min_token_total_supply..=self.max_token_total_supply)
+                .contains(&token_total_supply)
+    }
+}
+
+//RESERVES A CURVE CAN LAUNCH WITH AND STILL GRADUATE
+pub fn validate_launch_reserves(
+    virtual_sol_reserves: u64,
+    virtual_token_reserves: u64,
+    real_token_reserves: u64,
+    token_total_supply: u64,
+    migration_token_allocation: u64,
+) -> Result<()> {
+    require!(
+        virtual_sol_reserves > 0,
+        ContractError::InvalidGlobalSettings
+    );
+    require!(
+        real_token_reserves > 0 && real_token_reserves < virtual_token_reserves,
+        ContractError::InvalidGlobalSettings
+    );
+    //supply must cover the curve inventory and the migration allocation
+    require!(
+        real_token_reserves
+            .checked_add(migration_token_allocation)
+            .ok_or(ContractError::CalculationError)?
+            <= token_total_supply,
+        ContractError::InvalidGlobalSettings
+    );
+    Ok(())
+}
+
+impl GlobalSettingsInput {
+    pub fn validate(&self) -> Result<()> {
+        validate_launch_reserves(
+            self.initial_virtual_sol_reserves,
+            self.initial_virtual_token_reserves,
+            self.initial_real_token_reserves,
+            self.token_total_supply,
+            self.migration_token_allocation,
+        )?;
+        //defaults must be a valid pick for creators who do not override them
+        self.launch_bounds.validate()?;
+        require!(
+            self.launch_bounds.contains(
+                self.initial_virtual_sol_reserves,
+                self.initial_virtual_token_reserves,
+                self.initial_real_token_reserves,
+                self.token_total_supply,
+            ),
+            ContractError::InvalidGlobalSettings
+        );
+        require!(self.mint_decimals <= 9, ContractError::InvalidMintDecimals);
+        //the raise must cover the migration fee taken out of it
+        require!(
+            self.graduation_sol_threshold == 0
+                || self.graduation_sol_threshold > self.migrate_fee_amount,
+            ContractError::InvalidGlobalSettings
+        );
+        //an open window needs a cap that still lets wallets buy
+        require!(
+            self.launch_window_seconds >= 0,
+            ContractError::InvalidGlobalSettings
+        );
+        require!(
+            self.launch_window_seconds == 0 || self.launch_window_max_tokens > 0,
+            ContractError::InvalidGlobalSettings
+        );
+        require!(
+            self.fee_basis_points < Global::BASIS_POINTS_DIVISOR,
+            ContractError::InvalidGlobalSettings
+        );
+        require!(
+            self.creator_fee_basis_points <= Global::BASIS_POINTS_DIVISOR,
+            ContractError::InvalidGlobalSettings
+        );
+        require!(
+            self.referral_fee_basis_points <= Global::BASIS_POINTS_DIVISOR,
+            ContractError::InvalidGlobalSettings
+        );
+
+        Ok(())
+    }
+}
+
+//Shares of a trade fee paid to the fee vault, the creator vault and the referrer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeSplit {
+    pub total: u64,
+    pub protocol: u64,
+    pub creator: u64,
+    pub referral: u64,
 }
 
 impl Global {
     pub const SEED_PREFIX: &'static str = "global";
+    pub const BASIS_POINTS_DIVISOR: u64 = 10_000;
+
     pub fn update_settings(&mut self, params: GlobalSettingsInput) {
         self.initial_virtual_token_reserves = params.initial_virtual_token_reserves;
         self.initial_virtual_sol_reserves = params.initial_virtual_sol_reserves;
@@ -42,7 +206,107 @@ impl Global {
         self.mint_decimals = params.mint_decimals;
         self.migrate_fee_amount = params.migrate_fee_amount;
         self.migration_token_allocation = params.migration_token_allocation;
-        self.fee_receiver = params.fee_receiver;
+        self.graduation_sol_threshold = params.graduation_sol_threshold;
+        self.launch_window_seconds = params.launch_window_seconds;
+        self.launch_window_max_tokens = params.launch_window_max_tokens;
+        self.fee_basis_points = params.fee_basis_points;
+        self.creator_fee_basis_points = params.creator_fee_basis_points;
+        self.referral_fee_basis_points = params.referral_fee_basis_points;
         self.lp_config = params.lp_config;
+        self.launch_bounds = params.launch_bounds;
+    }
+
+    //FEE CHARGED ON THE SOL LEG OF A TRADE, ROUNDED UP FOR THE PROTOCOL
+    pub fn get_fee(&self, sol_amount: u64) -> Option<u64> {
+        let fee = (sol_amount as u128)
+            .checked_mul(self.fee_basis_points as u128)?
+            .checked_add(Self::BASIS_POINTS_DIVISOR as u128 - 1)?
+            .checked_div(Self::BASIS_POINTS_DIVISOR as u128)?;
+
+        fee.try_into().ok()
+    }
+
+    //CREATOR SHARE OF A TRADE FEE, ROUNDED DOWN SO THE PROTOCOL KEEPS THE REMAINDER
+    pub fn get_creator_fee(&self, fee_lamports: u64) -> Option<u64> {
+        let creator_fee = (fee_lamports as u128)
+            .checked_mul(self.creator_fee_basis_points as u128)?
+            .checked_div(Self::BASIS_POINTS_DIVISOR as u128)?;
+
+        creator_fee.try_into().ok()
+    }
+
+    //REFERRER SHARE OF THE PROTOCOL FEE, ROUNDED DOWN SO THE PROTOCOL KEEPS THE REMAINDER
+    pub fn get_referral_fee(&self, protocol_fee_lamports: u64) -> Option<u64> {
+        let referral_fee = (protocol_fee_lamports as u128)
+            .checked_mul(self.referral_fee_basis_points as u128)?
+            .checked_div(Self::BASIS_POINTS_DIVISOR as u128)?;
+
+        referral_fee.try_into().ok()
+    }
+
+    //SPLIT OF A TRADE FEE, THE REFERRER IS ONLY PAID WHEN THE TRADE WAS ROUTED THROUGH ONE
+    pub fn split_fee(&self, fee_lamports: u64, has_referrer: bool) -> Option<FeeSplit> {
+        let creator = self.get_creator_fee(fee_lamports)?;
+        let protocol_before_referral = fee_lamports.checked_sub(creator)?;
+        let referral = if has_referrer {
+            self.get_referral_fee(protocol_before_referral)?
+        } else {
+            0
+        };
+
+        Some(FeeSplit {
+            total: fee_lamports,
+            protocol: protocol_before_referral.checked_sub(referral)?,
+            creator,
+            referral,
+        })
+    }
+
+    //SOL AMOUNT WHOSE PROCEEDS AFTER FEES ARE AT LEAST `net_sol_amount`
+    pub fn get_sol_amount_before_fee(&self, net_sol_amount: u64) -> Option<u64> {
+        let divisor = Self::BASIS_POINTS_DIVISOR as u128;
+        let remaining = divisor.checked_sub(self.fee_basis_points as u128)?;
+        if remaining == 0 {
+            return None;
+        }
+
+        let sol_amount = (net_sol_amount as u128)
+            .checked_mul(divisor)?
+            .checked_add(remaining - 1)?
+            .checked_div(remaining)?;
+
+        sol_amount.try_into().ok()
+    }
+
+    pub fn current_authority(&self, role: AuthorityRole) -> Pubkey {
+        match role {
+            AuthorityRole::Global => self.global_authority,
+            AuthorityRole::Migration => self.migration_authority,
+        }
+    }
+
+    pub fn propose_authority(&mut self, role: AuthorityRole, new_authority: Pubkey) {
+        match role {
+            AuthorityRole::Global => self.pending_global_authority = new_authority,
+            AuthorityRole::Migration => self.pending_migration_authority = new_authority,
+        }
+    }
+
+    pub fn accept_authority(&mut self, role: AuthorityRole, new_authority: Pubkey) -> Result<()> {
+        let pending = match role {
+            AuthorityRole::Global => &mut self.pending_global_authority,
+            AuthorityRole::Migration => &mut self.pending_migration_authority,
+        };
+        require!(
+            *pending != Pubkey::default() && *pending == new_authority,
+            ContractError::WrongAuthority
+        );
+        *pending = Pubkey::default();
+
+        match role {
+            AuthorityRole::Global => self.global_authority = new_authority,
+            AuthorityRole::Migration => self.migration_authority = new_authority,
+        }
+        Ok(())
     }
 }
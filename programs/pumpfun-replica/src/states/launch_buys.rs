@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct LaunchBuys {
+    pub bonding_curve: Pubkey,
+    pub user: Pubkey,
+    //tokens the user bought on the curve during its launch window
+    pub tokens_bought: u64,
+    pub bump: u8,
+}
+
+impl LaunchBuys {
+    pub const SEED_PREFIX: &'static str = "launch-buys";
+
+    pub fn record_buy(&mut self, token_amount: u64) -> Option<()> {
+        self.tokens_bought = self.tokens_bought.checked_add(token_amount)?;
+        Some(())
+    }
+}
@@ -1,5 +1,20 @@
 pub use bonding_curve::*;
 pub mod bonding_curve;
 
+pub use creator_vault::*;
+pub mod creator_vault;
+
+pub use curve_pricing::*;
+pub mod curve_pricing;
+
+pub use fee_vault::*;
+pub mod fee_vault;
+
 pub use global::*;
 pub mod global;
+
+pub use launch_buys::*;
+pub mod launch_buys;
+
+pub use referrer_stats::*;
+pub mod referrer_stats;
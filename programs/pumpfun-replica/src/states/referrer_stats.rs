@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct ReferrerStats {
+    pub referrer: Pubkey,
+    //SOL traded through the referrer and their lifetime share of fees
+    pub referred_volume: u64,
+    pub fees_earned: u64,
+    pub bump: u8,
+}
+
+impl ReferrerStats {
+    pub const SEED_PREFIX: &'static str = "referrer-stats";
+
+    pub fn record_trade(&mut self, sol_amount: u64, referral_fee: u64) -> Option<()> {
+        self.referred_volume = self.referred_volume.checked_add(sol_amount)?;
+        self.fees_earned = self.fees_earned.checked_add(referral_fee)?;
+        Some(())
+    }
+}
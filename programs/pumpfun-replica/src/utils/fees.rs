@@ -0,0 +1,90 @@
+use crate::errors::*;
+use crate::states::{creator_vault::*, fee_vault::*, global::*, referrer_stats::*};
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke_signed, system_instruction},
+};
+
+//Accounts a trade fee is paid from and to. `payer` is the user on buys and the
+//SOL escrow on sells, in which case `signer_seeds` are the escrow seeds.
+pub struct FeeAccounts<'a, 'info> {
+    pub payer: AccountInfo<'info>,
+    pub fee_vault: &'a mut Account<'info, FeeVault>,
+    pub creator_vault: &'a mut Account<'info, CreatorVault>,
+    pub referrer: Option<AccountInfo<'info>>,
+    pub referrer_stats: Option<&'a mut Account<'info, ReferrerStats>>,
+    pub system_program: AccountInfo<'info>,
+}
+
+//referrer and its stats come together, and a user cannot refer themselves
+pub fn validate_referrer(
+    user: &Pubkey,
+    referrer: Option<&AccountInfo>,
+    referrer_stats: Option<&Account<ReferrerStats>>,
+) -> Result<()> {
+    match (referrer, referrer_stats) {
+        (None, None) => {}
+        (Some(referrer), Some(referrer_stats)) => {
+            require!(
+                referrer.key() == referrer_stats.referrer,
+                ContractError::InvalidReferrer
+            );
+            require!(referrer.key() != *user, ContractError::ReferrerIsUser);
+        }
+        _ => return err!(ContractError::InvalidReferrer),
+    }
+    Ok(())
+}
+
+//Splits `fee_lamports` and pays each share: the creator's share accrues in their
+//vault, the referrer's share goes straight to them and the rest to the fee vault.
+//`sol_amount` is the trade size credited to the referrer's volume.
+pub fn distribute_fees<'info>(
+    accounts: FeeAccounts<'_, 'info>,
+    global: &Global,
+    fee_lamports: u64,
+    sol_amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<FeeSplit> {
+    let FeeAccounts {
+        payer,
+        fee_vault,
+        creator_vault,
+        referrer,
+        referrer_stats,
+        system_program,
+    } = accounts;
+
+    let split = global
+        .split_fee(fee_lamports, referrer.is_some())
+        .ok_or(ContractError::CalculationError)?;
+
+    let transfer = |to: AccountInfo<'info>, lamports: u64| {
+        invoke_signed(
+            &system_instruction::transfer(payer.key, to.key, lamports),
+            &[payer.clone(), to, system_program.clone()],
+            signer_seeds,
+        )
+    };
+
+    //Collect Fee
+    transfer(fee_vault.to_account_info(), split.protocol)?;
+    fee_vault
+        .record_fee(split.protocol)
+        .ok_or(ContractError::CalculationError)?;
+
+    transfer(creator_vault.to_account_info(), split.creator)?;
+    creator_vault
+        .record_fee(split.creator)
+        .ok_or(ContractError::CalculationError)?;
+
+    //Pay referrer
+    if let (Some(referrer), Some(referrer_stats)) = (referrer, referrer_stats) {
+        transfer(referrer, split.referral)?;
+        referrer_stats
+            .record_trade(sol_amount, split.referral)
+            .ok_or(ContractError::CalculationError)?;
+    }
+
+    Ok(split)
+}
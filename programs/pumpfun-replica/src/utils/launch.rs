@@ -0,0 +1,227 @@
+use crate::errors::*;
+use crate::events::*;
+use crate::states::{bonding_curve::*, creator_vault::*, global::*};
+use anchor_lang::{prelude::*, solana_program::system_instruction};
+
+use anchor_spl::metadata::{
+    create_metadata_accounts_v3, mpl_token_metadata::types::DataV2, CreateMetadataAccountsV3,
+};
+use anchor_spl::token_interface::spl_pod::optional_keys::OptionalNonZeroPubkey;
+use anchor_spl::token_interface::spl_token_metadata_interface::state::TokenMetadata;
+use anchor_spl::token_interface::{
+    mint_to, set_authority, spl_token_2022::instruction::AuthorityType, token_metadata_initialize,
+    MintTo, SetAuthority, TokenMetadataInitialize,
+};
+
+//Accounts every curve launch touches, shared by create_bonding_curve and
+//create_bonding_curve_with_mint so both go through the same steps
+pub struct CurveLaunch<'a, 'info> {
+    pub creator: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    pub bonding_curve: &'a mut Account<'info, BondingCurve>,
+    pub bonding_curve_token_account: AccountInfo<'info>,
+    pub bonding_curve_sol_escrow: AccountInfo<'info>,
+    pub creator_vault: &'a mut Account<'info, CreatorVault>,
+    pub global: &'a Global,
+    pub system_program: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+}
+
+impl<'info> CurveLaunch<'_, 'info> {
+    //Sets up the curve state, the escrow and the creator's fee vault
+    pub fn initialize(
+        &mut self,
+        params: &CreateBondingCurveParams,
+        bonding_curve_bump: u8,
+        creator_vault_bump: u8,
+    ) -> Result<()> {
+        self.bonding_curve.update_from_params(
+            self.mint.key(),
+            self.creator.key(),
+            params,
+            self.global,
+            bonding_curve_bump,
+        )?;
+        self.fund_sol_escrow()?;
+
+        //Shared by all of the creator's curves, created on their first launch
+        self.creator_vault.creator = self.creator.key();
+        self.creator_vault.bump = creator_vault_bump;
+        Ok(())
+    }
+
+    //Escrow starts rent exempt so sells can never leave it holding dust below rent,
+    //its balance is then always real_sol_reserves plus rent
+    fn fund_sol_escrow(&self) -> Result<()> {
+        let rent_lamports = Rent::get()?
+            .minimum_balance(0)
+            .saturating_sub(self.bonding_curve_sol_escrow.lamports());
+        if rent_lamports > 0 {
+            anchor_lang::solana_program::program::invoke(
+                &system_instruction::transfer(
+                    self.creator.key,
+                    self.bonding_curve_sol_escrow.key,
+                    rent_lamports,
+                ),
+                &[
+                    self.creator.clone(),
+                    self.bonding_curve_sol_escrow.clone(),
+                    self.system_program.clone(),
+                ],
+            )?;
+        }
+        Ok(())
+    }
+
+    //SPL Token path, name/symbol/uri are stored in a Metaplex metadata account
+    pub fn set_metadata(
+        &self,
+        metadata: AccountInfo<'info>,
+        token_metadata_program: AccountInfo<'info>,
+        rent: AccountInfo<'info>,
+        mint_auth_signer_seeds: &[&[&[u8]]],
+        params: &CreateBondingCurveParams,
+    ) -> Result<()> {
+        let token_data: DataV2 = DataV2 {
+            name: params.name.clone(),
+            symbol: params.symbol.clone(),
+            uri: params.uri.clone(),
+            seller_fee_basis_points: 0,
+            creators: None,
+            collection: None,
+            uses: None,
+        };
+        let metadata_ctx = CpiContext::new_with_signer(
+            token_metadata_program,
+            CreateMetadataAccountsV3 {
+                payer: self.creator.clone(),
+                mint: self.mint.clone(),
+                metadata,
+                update_authority: self.bonding_curve.to_account_info(),
+                mint_authority: self.bonding_curve.to_account_info(),
+                system_program: self.system_program.clone(),
+                rent,
+            },
+            mint_auth_signer_seeds,
+        );
+        create_metadata_accounts_v3(metadata_ctx, token_data, false, true, None)?;
+        Ok(())
+    }
+
+    //Token-2022 path, name/symbol/uri are stored with the token metadata extension on the mint.
+    //The mint must already carry a metadata pointer to itself.
+    pub fn set_token_metadata(
+        &self,
+        mint_auth_signer_seeds: &[&[&[u8]]],
+        params: &CreateBondingCurveParams,
+    ) -> Result<()> {
+        let token_metadata = TokenMetadata {
+            update_authority: OptionalNonZeroPubkey::try_from(Some(self.bonding_curve.key()))?,
+            mint: self.mint.key(),
+            name: params.name.clone(),
+            symbol: params.symbol.clone(),
+            uri: params.uri.clone(),
+            additional_metadata: vec![],
+        };
+
+        //Top up the mint so it stays rent exempt once the token program reallocs it
+        let new_data_len = self
+            .mint
+            .data_len()
+            .checked_add(token_metadata.tlv_size_of()?)
+            .ok_or(ContractError::CalculationError)?;
+        let top_up_lamports = Rent::get()?
+            .minimum_balance(new_data_len)
+            .saturating_sub(self.mint.lamports());
+        if top_up_lamports > 0 {
+            anchor_lang::solana_program::program::invoke(
+                &system_instruction::transfer(self.creator.key, self.mint.key, top_up_lamports),
+                &[
+                    self.creator.clone(),
+                    self.mint.clone(),
+                    self.system_program.clone(),
+                ],
+            )?;
+        }
+
+        token_metadata_initialize(
+            CpiContext::new_with_signer(
+                self.token_program.clone(),
+                TokenMetadataInitialize {
+                    token_program_id: self.token_program.clone(),
+                    metadata: self.mint.clone(),
+                    update_authority: self.bonding_curve.to_account_info(),
+                    mint_authority: self.bonding_curve.to_account_info(),
+                    mint: self.mint.clone(),
+                },
+                mint_auth_signer_seeds,
+            ),
+            params.name.clone(),
+            params.symbol.clone(),
+            params.uri.clone(),
+        )
+    }
+
+    //Mints the whole supply to the curve and revokes the mint authority, plus the
+    //freeze authority when the mint has one, so the supply can never change
+    pub fn mint_and_lock(
+        &self,
+        mint_auth_signer_seeds: &[&[&[u8]]],
+        revoke_freeze_authority: bool,
+    ) -> Result<()> {
+        mint_to(
+            CpiContext::new_with_signer(
+                self.token_program.clone(),
+                MintTo {
+                    authority: self.bonding_curve.to_account_info(),
+                    to: self.bonding_curve_token_account.clone(),
+                    mint: self.mint.clone(),
+                },
+                mint_auth_signer_seeds,
+            ),
+            self.bonding_curve.token_total_supply,
+        )?;
+
+        self.revoke_authority(mint_auth_signer_seeds, AuthorityType::MintTokens)?;
+        if revoke_freeze_authority {
+            self.revoke_authority(mint_auth_signer_seeds, AuthorityType::FreezeAccount)?;
+        }
+        Ok(())
+    }
+
+    fn revoke_authority(
+        &self,
+        mint_auth_signer_seeds: &[&[&[u8]]],
+        authority_type: AuthorityType,
+    ) -> Result<()> {
+        set_authority(
+            CpiContext::new_with_signer(
+                self.token_program.clone(),
+                SetAuthority {
+                    current_authority: self.bonding_curve.to_account_info(),
+                    account_or_mint: self.mint.clone(),
+                },
+                mint_auth_signer_seeds,
+            ),
+            authority_type,
+            None,
+        )
+    }
+
+    pub fn emit_create_event(&self, params: CreateBondingCurveParams) -> Result<()> {
+        emit!(CreateEvent {
+            name: params.name,
+            symbol: params.symbol,
+            uri: params.uri,
+            mint: self.mint.key(),
+            bonding_curve: self.bonding_curve.key(),
+            creator: self.creator.key(),
+            virtual_sol_reserves: self.bonding_curve.virtual_sol_reserves,
+            virtual_token_reserves: self.bonding_curve.virtual_token_reserves,
+            real_token_reserves: self.bonding_curve.real_token_reserves,
+            token_total_supply: self.bonding_curve.token_total_supply,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+pub use fees::*;
+pub mod fees;
+
+pub use launch::*;
+pub mod launch;
@@ -2,6 +2,7 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { PublicKey, SystemProgram } from "@solana/web3.js";
 import { PumpfunReplica } from "../target/types/pumpfun_replica";
+import { MockAmm } from "../target/types/mock_amm";
 import { assert, expect } from "chai";
 import {
   AuthorityType,
@@ -10,15 +11,31 @@ import {
   MintLayout,
   setAuthority,
 } from "@solana/spl-token";
-import { getAccount } from "@solana/spl-token";
+import {
+  createInitializeMetadataPointerInstruction,
+  createInitializeMintInstruction,
+  ExtensionType,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getMint,
+  getMintLen,
+  getTokenMetadata,
+  TOKEN_2022_PROGRAM_ID,
+} from "@solana/spl-token";
 
 describe("pumpfun-replica", () => {
   // Configure the client to use the local cluster.
   anchor.setProvider(anchor.AnchorProvider.env());
   const provider = anchor.AnchorProvider.env();
   const program = anchor.workspace.PumpfunReplica as Program<PumpfunReplica>;
+  const mockAmm = anchor.workspace.MockAmm as Program<MockAmm>;
 
   const TOKEN_METADATA_PROGRAM_ID = new PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+  const launchBuysPDA = (bondingCurve: PublicKey, user: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("launch-buys"), bondingCurve.toBuffer(), user.toBuffer()],
+      program.programId
+    )[0];
   interface globalSettingsInput {
     initialVirtualTokenReserves: anchor.BN;
     initialVirtualSolReserves: anchor.BN;
@@ -27,21 +44,53 @@ describe("pumpfun-replica", () => {
     mintDecimals: number;
     migrateFeeAmount: anchor.BN;
     migrationTokenAllocation: anchor.BN;
-    feeReceiver: PublicKey;
+    graduationSolThreshold: anchor.BN;
+    launchWindowSeconds: anchor.BN;
+    launchWindowMaxTokens: anchor.BN;
+    feeBasisPoints: anchor.BN;
+    creatorFeeBasisPoints: anchor.BN;
+    referralFeeBasisPoints: anchor.BN;
     lpConfig: PublicKey;
+    launchBounds: {
+      minVirtualSolReserves: anchor.BN;
+      maxVirtualSolReserves: anchor.BN;
+      minVirtualTokenReserves: anchor.BN;
+      maxVirtualTokenReserves: anchor.BN;
+      minRealTokenReserves: anchor.BN;
+      maxRealTokenReserves: anchor.BN;
+      minTokenTotalSupply: anchor.BN;
+      maxTokenTotalSupply: anchor.BN;
+    };
   }
 
+  const launchBounds = {
+    minVirtualSolReserves: new anchor.BN(10 * 1_000_000_000),
+    maxVirtualSolReserves: new anchor.BN(100 * 1_000_000_000),
+    minVirtualTokenReserves: new anchor.BN(500000000000000),
+    maxVirtualTokenReserves: new anchor.BN(2000000000000000),
+    minRealTokenReserves: new anchor.BN(400000000000000),
+    maxRealTokenReserves: new anchor.BN(1500000000000000),
+    minTokenTotalSupply: new anchor.BN(500000000000000),
+    maxTokenTotalSupply: new anchor.BN(2000000000000000),
+  };
+
   let globalPDA;
+  let feeVaultPDA;
+  let creatorVaultPDA;
   let bondingCurvePDA;
   let bondingCurveSolEscrowPDA;
   let metadataPDA;
   let bondingCurveTokenAccount;
+  let tokenMint2022;
+  let bondingCurve2022PDA;
   let userTokenAccount;
   // let FEE_RECEIVER = new PublicKey("Bf8PxxWt7UTvNGcrDyNwQiERSwNroa4pEo1pxwKo17Uh");
   let FEE_RECEIVER = anchor.web3.Keypair.generate();
   let admin = anchor.web3.Keypair.generate();
   let creator1 = anchor.web3.Keypair.generate();
   let user1 = anchor.web3.Keypair.generate();
+  let whale = anchor.web3.Keypair.generate();
+  let referrer = anchor.web3.Keypair.generate();
 
   console.log("Admin Publickey", admin.publicKey.toString());
   console.log("Creator Publickey", creator1.publicKey.toString());
@@ -85,10 +134,18 @@ describe("pumpfun-replica", () => {
     globalPDA = derivePDA(["global"]);
     console.log("Global PDA", globalPDA);
 
+    //DERIVE FEE VAULT
+    feeVaultPDA = derivePDA(["fee-vault"]);
+
     //AIRDROP ACCOUNTS
     await airdropSOL(admin.publicKey, 1);
     await airdropSOL(creator1.publicKey, 1);
     await airdropSOL(user1.publicKey, 5);
+    await airdropSOL(whale.publicKey, 100);
+    await airdropSOL(referrer.publicKey, 1);
+
+    //DERIVE CREATOR VAULT
+    creatorVaultPDA = derivePDA(["creator-vault", creator1.publicKey]);
 
     //CREATE TOKEN MINT 1
     tokenMint1 = await createMint(provider.connection, creator1, creator1.publicKey, null, 6);
@@ -158,8 +215,14 @@ describe("pumpfun-replica", () => {
       mintDecimals: 6,
       migrateFeeAmount: new anchor.BN(500),
       migrationTokenAllocation: new anchor.BN(50000000000000),
-      feeReceiver: FEE_RECEIVER.publicKey,
-      lpConfig: PublicKey.default,
+      graduationSolThreshold: new anchor.BN(0),
+      launchWindowSeconds: new anchor.BN(0),
+      launchWindowMaxTokens: new anchor.BN(0),
+      feeBasisPoints: new anchor.BN(50),
+      creatorFeeBasisPoints: new anchor.BN(2_000),
+      referralFeeBasisPoints: new anchor.BN(5_000),
+      lpConfig: mockAmm.programId,
+      launchBounds,
     };
 
     const tx = await program.methods
@@ -167,14 +230,16 @@ describe("pumpfun-replica", () => {
       .accounts({
         authority: admin.publicKey,
         global: globalPDA,
+        feeVault: feeVaultPDA,
         systemProgram: SystemProgram.programId,
       })
       .signers([admin])
       .rpc();
 
     const state = await program.account.global.fetch(globalPDA);
+    const feeVault = await program.account.feeVault.fetch(feeVaultPDA);
     assert.strictEqual(state.tokenTotalSupply.toNumber(), 1000000000000000);
-    assert.strictEqual(state.feeReceiver.toString(), FEE_RECEIVER.publicKey.toString());
+    assert.strictEqual(feeVault.totalFeesCollected.toNumber(), 0);
     assert.strictEqual(state.initialized, true);
     console.log("Your transaction signature", tx);
   });
@@ -184,6 +249,11 @@ describe("pumpfun-replica", () => {
       name: "Biboshi",
       symbol: "BSH",
       uri: "https://gateway.pinata.cloud/ipfs/bafkreig2zeo4l3suy3tlaqzhw4u5zsdkmmq7nnjfkbpt3qfjycna5hrgxm",
+      curveKind: null,
+      initialVirtualSolReserves: null,
+      initialVirtualTokenReserves: null,
+      initialRealTokenReserves: null,
+      tokenTotalSupply: null,
     };
 
     const tx = await program.methods
@@ -191,6 +261,7 @@ describe("pumpfun-replica", () => {
       .accounts({
         mint: tokenMint1,
         creator: creator1.publicKey,
+        creatorVault: creatorVaultPDA,
         bondingCurve: bondingCurvePDA,
         bondingCurveTokenAccount: bondingCurveTokenAccount.address,
         bondingCurveSolEscrow: bondingCurveSolEscrowPDA,
@@ -209,33 +280,231 @@ describe("pumpfun-replica", () => {
     assert.strictEqual(bonding_curve.realSolReserves.toNumber(), 0);
     assert.strictEqual(bonding_curve.complete, false);
     assert.strictEqual(bonding_curve.virtualTokenReserves.toNumber(), 1073000000000000);
+    //CURVE KIND DEFAULTS TO CONSTANT PRODUCT
+    assert.deepEqual(bonding_curve.curveKind, { constantProduct: {} });
+
+    //MINT AND FREEZE AUTHORITIES ARE REVOKED
+    const mintInfo = await getMint(provider.connection, tokenMint1);
+    assert.strictEqual(mintInfo.mintAuthority, null);
+    assert.strictEqual(mintInfo.freezeAuthority, null);
+    assert.strictEqual(mintInfo.supply.toString(), "1000000000000000");
   });
 
-  it("Buy and sell from/to bonding curve", async () => {
-    const tx = await program.methods
-      .buy(SOL_FOR_BUY)
+  //CURRENT GLOBAL SETTINGS WITH `overrides` APPLIED, AS SET_PARAMS INPUT
+  const settingsWith = async (
+    overrides: Partial<globalSettingsInput> = {}
+  ): Promise<globalSettingsInput> => {
+    const state = await program.account.global.fetch(globalPDA);
+    return {
+      initialVirtualTokenReserves: state.initialVirtualTokenReserves,
+      initialVirtualSolReserves: state.initialVirtualSolReserves,
+      initialRealTokenReserves: state.initialRealTokenReserves,
+      tokenTotalSupply: state.tokenTotalSupply,
+      mintDecimals: state.mintDecimals,
+      migrateFeeAmount: state.migrateFeeAmount,
+      migrationTokenAllocation: state.migrationTokenAllocation,
+      graduationSolThreshold: state.graduationSolThreshold,
+      launchWindowSeconds: state.launchWindowSeconds,
+      launchWindowMaxTokens: state.launchWindowMaxTokens,
+      feeBasisPoints: state.feeBasisPoints,
+      creatorFeeBasisPoints: state.creatorFeeBasisPoints,
+      referralFeeBasisPoints: state.referralFeeBasisPoints,
+      lpConfig: state.lpConfig,
+      launchBounds: state.launchBounds,
+      ...overrides,
+    };
+  };
+
+  //CREATOR1 LAUNCHES A CURVE ON A MINT THE PROGRAM CREATES, `params` OVERRIDE THE DEFAULT LAUNCH
+  const createCurve = async (params = {}) => {
+    const mintKeypair = anchor.web3.Keypair.generate();
+    const mint = mintKeypair.publicKey;
+    const curvePDA = PublicKey.findProgramAddressSync(
+      [Buffer.from("bonding-curve"), mint.toBuffer()],
+      program.programId
+    )[0];
+    const curveTokenAccount = getAssociatedTokenAddressSync(mint, curvePDA, true);
+    const curveSolEscrow = PublicKey.findProgramAddressSync(
+      [Buffer.from("sol-escrow"), mint.toBuffer()],
+      program.programId
+    )[0];
+
+    await program.methods
+      .createBondingCurveWithMint({
+        name: "Curve",
+        symbol: "CRV",
+        uri: "",
+        curveKind: null,
+        initialVirtualSolReserves: null,
+        initialVirtualTokenReserves: null,
+        initialRealTokenReserves: null,
+        tokenTotalSupply: null,
+        ...params,
+      })
       .accounts({
-        user: user1.publicKey,
+        creator: creator1.publicKey,
+        creatorVault: creatorVaultPDA,
         global: globalPDA,
-        feeReceiver: FEE_RECEIVER.publicKey,
-        mint: tokenMint1,
-        bondingCurve: bondingCurvePDA,
-        bondingCurveTokenAccount: bondingCurveTokenAccount.address,
-        bondingCurveSolEscrow: bondingCurveSolEscrowPDA,
-        userTokenAccount: userTokenAccount.address,
+        mint,
+        bondingCurve: curvePDA,
+        bondingCurveTokenAccount: curveTokenAccount,
+        bondingCurveSolEscrow: curveSolEscrow,
+        metadata: PublicKey.findProgramAddressSync(
+          [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+          TOKEN_METADATA_PROGRAM_ID
+        )[0],
         systemProgram: SystemProgram.programId,
         tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
         associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
         tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
         rent: anchor.web3.SYSVAR_RENT_PUBKEY,
       })
+      .signers([creator1, mintKeypair])
+      .rpc();
+
+    return { mint, curvePDA, curveTokenAccount, curveSolEscrow };
+  };
+
+  //ACCOUNTS FOR A BUY OR SELL BY `user` ON THE CURVE OF `mint`, `overrides` REPLACE ANY OF THEM
+  const tradeAccounts = (
+    mint: PublicKey,
+    user: PublicKey,
+    overrides: { [account: string]: PublicKey | null } = {}
+  ) => {
+    const tokenProgram = overrides.tokenProgram ?? anchor.utils.token.TOKEN_PROGRAM_ID;
+    const curvePDA = PublicKey.findProgramAddressSync(
+      [Buffer.from("bonding-curve"), mint.toBuffer()],
+      program.programId
+    )[0];
+    return {
+      user,
+      global: globalPDA,
+      feeVault: feeVaultPDA,
+      creatorVault: creatorVaultPDA,
+      referrer: null,
+      referrerStats: null,
+      mint,
+      bondingCurve: curvePDA,
+      bondingCurveTokenAccount: getAssociatedTokenAddressSync(mint, curvePDA, true, tokenProgram),
+      bondingCurveSolEscrow: PublicKey.findProgramAddressSync(
+        [Buffer.from("sol-escrow"), mint.toBuffer()],
+        program.programId
+      )[0],
+      userTokenAccount: getAssociatedTokenAddressSync(mint, user, true, tokenProgram),
+      launchBuys: null,
+      systemProgram: SystemProgram.programId,
+      tokenProgram,
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+      ...overrides,
+    };
+  };
+
+  it("Bonding Curve Created with program initialised mint", async () => {
+    const { mint } = await createCurve({ name: "Biboshi Two", symbol: "BSH2" });
+
+    const mintInfo = await getMint(provider.connection, mint);
+    assert.strictEqual(mintInfo.decimals, 6);
+    assert.strictEqual(mintInfo.mintAuthority, null);
+    assert.strictEqual(mintInfo.supply.toString(), "1000000000000000");
+  });
+
+  //TOKEN-2022 MINT WITH ONLY A METADATA POINTER, OWNED BY ITS FUTURE BONDING CURVE
+  const createMint2022 = async (metadataAddress?: PublicKey) => {
+    const mintKeypair = anchor.web3.Keypair.generate();
+    const curvePDA = PublicKey.findProgramAddressSync(
+      [Buffer.from("bonding-curve"), mintKeypair.publicKey.toBuffer()],
+      program.programId
+    )[0];
+    const mintLen = getMintLen([ExtensionType.MetadataPointer]);
+    const createMintTx = new anchor.web3.Transaction().add(
+      SystemProgram.createAccount({
+        fromPubkey: creator1.publicKey,
+        newAccountPubkey: mintKeypair.publicKey,
+        space: mintLen,
+        lamports: await provider.connection.getMinimumBalanceForRentExemption(mintLen),
+        programId: TOKEN_2022_PROGRAM_ID,
+      }),
+      createInitializeMetadataPointerInstruction(
+        mintKeypair.publicKey,
+        curvePDA,
+        metadataAddress ?? mintKeypair.publicKey,
+        TOKEN_2022_PROGRAM_ID
+      ),
+      createInitializeMintInstruction(mintKeypair.publicKey, 6, curvePDA, null, TOKEN_2022_PROGRAM_ID)
+    );
+    await anchor.web3.sendAndConfirmTransaction(provider.connection, createMintTx, [
+      creator1,
+      mintKeypair,
+    ]);
+    return { mint: mintKeypair.publicKey, curvePDA };
+  };
+
+  const createBondingCurve2022 = (mint: PublicKey, curvePDA: PublicKey) =>
+    program.methods
+      .createBondingCurve({
+        name: "Biboshi 2022",
+        symbol: "BSH22",
+        uri: "https://example.com",
+        curveKind: null,
+        initialVirtualSolReserves: null,
+        initialVirtualTokenReserves: null,
+        initialRealTokenReserves: null,
+        tokenTotalSupply: null,
+      })
+      .accounts({
+        mint,
+        creator: creator1.publicKey,
+        creatorVault: creatorVaultPDA,
+        bondingCurve: curvePDA,
+        bondingCurveTokenAccount: getAssociatedTokenAddressSync(
+          mint,
+          curvePDA,
+          true,
+          TOKEN_2022_PROGRAM_ID
+        ),
+        bondingCurveSolEscrow: PublicKey.findProgramAddressSync(
+          [Buffer.from("sol-escrow"), mint.toBuffer()],
+          program.programId
+        )[0],
+        global: globalPDA,
+        metadata: null,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        tokenMetadataProgram: null,
+        rent: null,
+      })
+      .signers([creator1])
+      .rpc();
+
+  it("Bonding Curve Created for a Token-2022 mint", async () => {
+    const { mint, curvePDA } = await createMint2022();
+    tokenMint2022 = mint;
+    bondingCurve2022PDA = curvePDA;
+
+    await createBondingCurve2022(tokenMint2022, bondingCurve2022PDA);
+
+    const tokenMetadata = await getTokenMetadata(provider.connection, tokenMint2022);
+    assert.strictEqual(tokenMetadata.name, "Biboshi 2022");
+    assert.strictEqual(tokenMetadata.symbol, "BSH22");
+
+    const mintInfo = await getMint(provider.connection, tokenMint2022, undefined, TOKEN_2022_PROGRAM_ID);
+    assert.strictEqual(mintInfo.mintAuthority, null);
+    assert.strictEqual(mintInfo.supply.toString(), "1000000000000000");
+  });
+
+  it("Buy and sell from/to bonding curve", async () => {
+    const tx = await program.methods
+      .buy(SOL_FOR_BUY, new anchor.BN(0))
+      .accounts(tradeAccounts(tokenMint1, user1.publicKey))
       .signers([user1])
       .rpc();
 
     const userTokenAccountInfo = await getAccount(provider.connection, userTokenAccount.address);
     const bonding_curve = await program.account.bondingCurve.fetch(bondingCurvePDA);
     const globalState = await program.account.global.fetch(globalPDA);
-    const feeReceiverBalance = await provider.connection.getBalance(FEE_RECEIVER.publicKey);
+    const feeVault = await program.account.feeVault.fetch(feeVaultPDA);
+    const creatorVault = await program.account.creatorVault.fetch(creatorVaultPDA);
     const userBalanceAfterBuy = await provider.connection.getBalance(user1.publicKey);
 
     //EQUIVALENT FOR FIRST BUY ON CURVE USING 0.2 SOL
@@ -245,7 +514,9 @@ describe("pumpfun-replica", () => {
       bonding_curve.virtualTokenReserves.toNumber(),
       globalState.initialVirtualTokenReserves.toNumber() - 7105960264900
     );
-    assert.strictEqual(feeReceiverBalance, 1000000);
+    //50 BPS FEE ON 0.2 SOL, 20% OF IT TO THE CREATOR
+    assert.strictEqual(feeVault.totalFeesCollected.toNumber(), 800000);
+    assert.strictEqual(creatorVault.totalFeesAccrued.toNumber(), 200000);
     // await provider.connection.getBalance(user1.publicKey);
 
     // console.log(
@@ -254,22 +525,8 @@ describe("pumpfun-replica", () => {
     // );
 
     const tx2 = await program.methods
-      .sell(TOKEN_FOR_SELL)
-      .accounts({
-        user: user1.publicKey,
-        global: globalPDA,
-        feeReceiver: FEE_RECEIVER.publicKey,
-        mint: tokenMint1,
-        bondingCurve: bondingCurvePDA,
-        bondingCurveTokenAccount: bondingCurveTokenAccount.address,
-        bondingCurveSolEscrow: bondingCurveSolEscrowPDA,
-        userTokenAccount: userTokenAccount.address,
-        systemProgram: SystemProgram.programId,
-        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
-        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
-        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
-        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-      })
+      .sell(TOKEN_FOR_SELL, new anchor.BN(0))
+      .accounts(tradeAccounts(tokenMint1, user1.publicKey))
       .signers([user1])
       .rpc();
 
@@ -289,4 +546,1159 @@ describe("pumpfun-replica", () => {
     assert.strictEqual(userTokenAccountInfoAfterSell.amount.toString(), "2105960264900");
     expect(userBalanceAfterSell).to.be.greaterThan(userBalanceAfterBuy);
   });
+
+  it("Token-2022 mint with a metadata pointer to another account is rejected", async () => {
+    const { mint, curvePDA } = await createMint2022(anchor.web3.Keypair.generate().publicKey);
+    try {
+      await createBondingCurve2022(mint, curvePDA);
+      assert.fail("create should have failed");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("InvalidMetadataPointer");
+    }
+  });
+
+  it("Buy and sell on a Token-2022 bonding curve", async () => {
+    const curveTokenAccount = getAssociatedTokenAddressSync(
+      tokenMint2022,
+      bondingCurve2022PDA,
+      true,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const user1TokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user1,
+      tokenMint2022,
+      user1.publicKey,
+      false,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const user1Accounts = tradeAccounts(tokenMint2022, user1.publicKey, {
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    });
+    const quoteAccounts = {
+      global: globalPDA,
+      mint: tokenMint2022,
+      bondingCurve: bondingCurve2022PDA,
+    };
+
+    const buyQuote = await program.methods.quoteBuy(SOL_FOR_BUY).accounts(quoteAccounts).view();
+    await program.methods
+      .buy(SOL_FOR_BUY, new anchor.BN(0))
+      .accounts(user1Accounts)
+      .signers([user1])
+      .rpc();
+
+    let userTokens = await getAccount(
+      provider.connection,
+      user1TokenAccount.address,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.strictEqual(userTokens.amount.toString(), buyQuote.tokenAmount.toString());
+
+    //SELL EVERYTHING BACK, THE CURVE INVENTORY IS RESTORED
+    await program.methods
+      .sell(buyQuote.tokenAmount, new anchor.BN(0))
+      .accounts(user1Accounts)
+      .signers([user1])
+      .rpc();
+
+    userTokens = await getAccount(
+      provider.connection,
+      user1TokenAccount.address,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const curve = await program.account.bondingCurve.fetch(bondingCurve2022PDA);
+    const curveTokens = await getAccount(
+      provider.connection,
+      curveTokenAccount,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.strictEqual(userTokens.amount.toString(), "0");
+    assert.strictEqual(curve.realTokenReserves.toString(), curve.initialRealTokenReserves.toString());
+    assert.strictEqual(curveTokens.amount.toString(), curve.tokenTotalSupply.toString());
+  });
+
+  it("Buy fails when tokens out is below min_tokens_out", async () => {
+    try {
+      await program.methods
+        .buy(SOL_FOR_BUY, new anchor.BN("1000000000000000"))
+        .accounts(tradeAccounts(tokenMint1, user1.publicKey))
+        .signers([user1])
+        .rpc();
+      assert.fail("buy should have failed");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("TooLittleTokensReceived");
+    }
+  });
+
+  it("Sell fails when net SOL out is below min_sol_output", async () => {
+    try {
+      await program.methods
+        .sell(new anchor.BN(1000000000000), new anchor.BN(1_000_000_000))
+        .accounts(tradeAccounts(tokenMint1, user1.publicKey))
+        .signers([user1])
+        .rpc();
+      assert.fail("sell should have failed");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("TooLittleSolReceived");
+    }
+  });
+
+  it("Buy exact amount of tokens", async () => {
+    const tokenAmount = new anchor.BN(1000000000000);
+    const userTokenAccountBefore = await getAccount(provider.connection, userTokenAccount.address);
+    const quote = await program.methods
+      .quoteBuyExactTokens(tokenAmount)
+      .accounts({ global: globalPDA, mint: tokenMint1, bondingCurve: bondingCurvePDA })
+      .view();
+    const realSolBefore = (await program.account.bondingCurve.fetch(bondingCurvePDA)).realSolReserves;
+
+    const tx = await program.methods
+      .buyExactTokens(tokenAmount, new anchor.BN(1_000_000_000))
+      .accounts(tradeAccounts(tokenMint1, user1.publicKey))
+      .signers([user1])
+      .rpc({ commitment: "confirmed" });
+
+    const userTokenAccountAfter = await getAccount(provider.connection, userTokenAccount.address);
+    const tokensReceived = new anchor.BN(userTokenAccountAfter.amount.toString()).sub(
+      new anchor.BN(userTokenAccountBefore.amount.toString())
+    );
+    assert.strictEqual(tokensReceived.toString(), tokenAmount.toString());
+    assert.strictEqual(quote.tokenAmount.toString(), tokenAmount.toString());
+    const realSolAfter = (await program.account.bondingCurve.fetch(bondingCurvePDA)).realSolReserves;
+    assert.strictEqual(realSolAfter.sub(realSolBefore).toString(), quote.solAmount.toString());
+
+    //DECODE TRADE EVENT FROM TRANSACTION LOGS
+    const txDetails = await provider.connection.getTransaction(tx, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const eventParser = new anchor.EventParser(program.programId, program.coder);
+    const events = [...eventParser.parseLogs(txDetails.meta.logMessages)];
+    const tradeEvent = events.find((event) => event.name == "tradeEvent");
+    assert.isDefined(tradeEvent);
+    assert.strictEqual(tradeEvent.data.isBuy, true);
+    assert.strictEqual(tradeEvent.data.tokenAmount.toString(), tokenAmount.toString());
+    assert.strictEqual(tradeEvent.data.mint.toString(), tokenMint1.toString());
+  });
+
+  it("Sell tokens for exact amount of SOL", async () => {
+    const solAmount = 10_000_000;
+    const userBalanceBefore = await provider.connection.getBalance(user1.publicKey);
+
+    await program.methods
+      .sellForExactSol(new anchor.BN(solAmount), new anchor.BN(1000000000000))
+      .accounts(tradeAccounts(tokenMint1, user1.publicKey))
+      .signers([user1])
+      .rpc();
+
+    const userBalanceAfter = await provider.connection.getBalance(user1.publicKey);
+    //fee is taken from the proceeds, the user receives exactly the requested amount
+    assert.strictEqual(userBalanceAfter - userBalanceBefore, solAmount);
+  });
+
+  it("Quotes match the executed buy and sell", async () => {
+    const user1Accounts = tradeAccounts(tokenMint1, user1.publicKey);
+    const quoteAccounts = { global: globalPDA, mint: tokenMint1, bondingCurve: bondingCurvePDA };
+
+    //BUY
+    const buyQuote = await program.methods.quoteBuy(SOL_FOR_BUY).accounts(quoteAccounts).view();
+    const tokensBefore = new anchor.BN(
+      (await getAccount(provider.connection, userTokenAccount.address)).amount.toString()
+    );
+    await program.methods
+      .buy(SOL_FOR_BUY, new anchor.BN(0))
+      .accounts(user1Accounts)
+      .signers([user1])
+      .rpc();
+    const tokensAfter = new anchor.BN(
+      (await getAccount(provider.connection, userTokenAccount.address)).amount.toString()
+    );
+    let bondingCurve = await program.account.bondingCurve.fetch(bondingCurvePDA);
+
+    assert.strictEqual(tokensAfter.sub(tokensBefore).toString(), buyQuote.tokenAmount.toString());
+    assert.strictEqual(buyQuote.solAmount.toString(), SOL_FOR_BUY.toString());
+    assert.strictEqual(bondingCurve.virtualSolReserves.toString(), buyQuote.virtualSolReserves.toString());
+    assert.strictEqual(bondingCurve.virtualTokenReserves.toString(), buyQuote.virtualTokenReserves.toString());
+    assert.strictEqual(bondingCurve.realSolReserves.toString(), buyQuote.realSolReserves.toString());
+    assert.strictEqual(bondingCurve.realTokenReserves.toString(), buyQuote.realTokenReserves.toString());
+
+    //SELL BACK WHAT WAS BOUGHT
+    const sellQuote = await program.methods
+      .quoteSell(buyQuote.tokenAmount)
+      .accounts(quoteAccounts)
+      .view();
+    const userBalanceBefore = await provider.connection.getBalance(user1.publicKey);
+    await program.methods
+      .sell(buyQuote.tokenAmount, new anchor.BN(0))
+      .accounts(user1Accounts)
+      .signers([user1])
+      .rpc();
+    const userBalanceAfter = await provider.connection.getBalance(user1.publicKey);
+    bondingCurve = await program.account.bondingCurve.fetch(bondingCurvePDA);
+
+    assert.strictEqual(
+      userBalanceAfter - userBalanceBefore,
+      sellQuote.solAmount.sub(sellQuote.fee).toNumber()
+    );
+    assert.strictEqual(bondingCurve.virtualSolReserves.toString(), sellQuote.virtualSolReserves.toString());
+    assert.strictEqual(bondingCurve.realSolReserves.toString(), sellQuote.realSolReserves.toString());
+    assert.strictEqual(bondingCurve.realTokenReserves.toString(), sellQuote.realTokenReserves.toString());
+  });
+
+  it("Global pause and curve halt stop trading", async () => {
+    const user1Accounts = tradeAccounts(tokenMint1, user1.publicKey);
+
+    try {
+      await program.methods
+        .setPaused(true)
+        .accounts({ authority: user1.publicKey, global: globalPDA })
+        .signers([user1])
+        .rpc();
+      assert.fail("set_paused should have failed");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("WrongAuthority");
+    }
+
+    //PAUSED: NO TRADES AND NO NEW CURVES
+    await program.methods
+      .setPaused(true)
+      .accounts({ authority: admin.publicKey, global: globalPDA })
+      .signers([admin])
+      .rpc();
+
+    try {
+      await program.methods
+        .buy(SOL_FOR_BUY, new anchor.BN(0))
+        .accounts(user1Accounts)
+        .signers([user1])
+        .rpc();
+      assert.fail("buy should have failed");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("GlobalPaused");
+    }
+
+    try {
+      await createCurve({ name: "Paused", symbol: "PSD" });
+      assert.fail("create_bonding_curve_with_mint should have failed");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("GlobalPaused");
+    }
+
+    await program.methods
+      .setPaused(false)
+      .accounts({ authority: admin.publicKey, global: globalPDA })
+      .signers([admin])
+      .rpc();
+
+    //HALTED: ONLY THIS CURVE IS BLOCKED
+    const haltAccounts = {
+      authority: admin.publicKey,
+      global: globalPDA,
+      mint: tokenMint1,
+      bondingCurve: bondingCurvePDA,
+    };
+    await program.methods.setHalted(true).accounts(haltAccounts).signers([admin]).rpc();
+    assert.strictEqual((await program.account.bondingCurve.fetch(bondingCurvePDA)).halted, true);
+
+    try {
+      await program.methods
+        .sell(TOKEN_FOR_SELL, new anchor.BN(0))
+        .accounts(user1Accounts)
+        .signers([user1])
+        .rpc();
+      assert.fail("sell should have failed");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("BondingCurveHalted");
+    }
+
+    await program.methods.setHalted(false).accounts(haltAccounts).signers([admin]).rpc();
+    assert.strictEqual((await program.account.bondingCurve.fetch(bondingCurvePDA)).halted, false);
+  });
+
+  it("Referrer earns a share of the protocol fee on routed trades", async () => {
+    const [referrerStatsPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("referrer-stats"), referrer.publicKey.toBuffer()],
+      program.programId
+    );
+    const [user1StatsPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("referrer-stats"), user1.publicKey.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .registerReferrer()
+      .accounts({
+        referrer: referrer.publicKey,
+        referrerStats: referrerStatsPDA,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([referrer])
+      .rpc();
+    await program.methods
+      .registerReferrer()
+      .accounts({
+        referrer: user1.publicKey,
+        referrerStats: user1StatsPDA,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user1])
+      .rpc();
+
+    const routedAccounts = (referrerKey: PublicKey, statsKey: PublicKey) =>
+      tradeAccounts(tokenMint1, user1.publicKey, { referrer: referrerKey, referrerStats: statsKey });
+
+    //A USER CANNOT REFER THEIR OWN TRADES
+    try {
+      await program.methods
+        .buy(SOL_FOR_BUY, new anchor.BN(0))
+        .accounts(routedAccounts(user1.publicKey, user1StatsPDA))
+        .signers([user1])
+        .rpc();
+      assert.fail("buy should have failed");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("ReferrerIsUser");
+    }
+
+    //STATS MUST BELONG TO THE REFERRER
+    try {
+      await program.methods
+        .buy(SOL_FOR_BUY, new anchor.BN(0))
+        .accounts(routedAccounts(referrer.publicKey, user1StatsPDA))
+        .signers([user1])
+        .rpc();
+      assert.fail("buy should have failed");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("InvalidReferrer");
+    }
+
+    const referrerBalanceBefore = await provider.connection.getBalance(referrer.publicKey);
+    await program.methods
+      .buy(SOL_FOR_BUY, new anchor.BN(0))
+      .accounts(routedAccounts(referrer.publicKey, referrerStatsPDA))
+      .signers([user1])
+      .rpc();
+
+    //50 BPS FEE ON 0.2 SOL, 20% TO THE CREATOR, HALF OF THE REST TO THE REFERRER
+    const referrerBalanceAfterBuy = await provider.connection.getBalance(referrer.publicKey);
+    let stats = await program.account.referrerStats.fetch(referrerStatsPDA);
+    assert.strictEqual(referrerBalanceAfterBuy - referrerBalanceBefore, 400000);
+    assert.strictEqual(stats.feesEarned.toNumber(), 400000);
+    assert.strictEqual(stats.referredVolume.toString(), SOL_FOR_BUY.toString());
+
+    const bondingCurveBeforeSell = await program.account.bondingCurve.fetch(bondingCurvePDA);
+    await program.methods
+      .sell(TOKEN_FOR_SELL, new anchor.BN(0))
+      .accounts(routedAccounts(referrer.publicKey, referrerStatsPDA))
+      .signers([user1])
+      .rpc();
+
+    const bondingCurveAfterSell = await program.account.bondingCurve.fetch(bondingCurvePDA);
+    const referrerBalanceAfterSell = await provider.connection.getBalance(referrer.publicKey);
+    stats = await program.account.referrerStats.fetch(referrerStatsPDA);
+    const soldFor = bondingCurveBeforeSell.realSolReserves.sub(bondingCurveAfterSell.realSolReserves);
+    assert.strictEqual(stats.referredVolume.toString(), SOL_FOR_BUY.add(soldFor).toString());
+    assert.strictEqual(
+      referrerBalanceAfterSell - referrerBalanceBefore,
+      stats.feesEarned.toNumber()
+    );
+
+    //EXACT IN/OUT TRADES PAY THE REFERRER THE SAME WAY
+    const feesEarnedBefore = stats.feesEarned;
+    await program.methods
+      .buyExactTokens(TOKEN_FOR_SELL, new anchor.BN(1_000_000_000))
+      .accounts(routedAccounts(referrer.publicKey, referrerStatsPDA))
+      .signers([user1])
+      .rpc();
+    await program.methods
+      .sellForExactSol(new anchor.BN(10_000_000), new anchor.BN(1000000000000))
+      .accounts(routedAccounts(referrer.publicKey, referrerStatsPDA))
+      .signers([user1])
+      .rpc();
+
+    const referrerBalanceAfterExact = await provider.connection.getBalance(referrer.publicKey);
+    stats = await program.account.referrerStats.fetch(referrerStatsPDA);
+    assert.isTrue(stats.feesEarned.gt(feesEarnedBefore));
+    assert.strictEqual(
+      referrerBalanceAfterExact - referrerBalanceBefore,
+      stats.feesEarned.toNumber()
+    );
+  });
+
+  it("Global authority updates settings", async () => {
+    const newParams = await settingsWith({ migrateFeeAmount: new anchor.BN(1000) });
+
+    try {
+      await program.methods
+        .setParams(newParams)
+        .accounts({ authority: user1.publicKey, global: globalPDA })
+        .signers([user1])
+        .rpc();
+      assert.fail("set_params should have failed");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("WrongAuthority");
+    }
+
+    await program.methods
+      .setParams(newParams)
+      .accounts({ authority: admin.publicKey, global: globalPDA })
+      .signers([admin])
+      .rpc();
+
+    //CURVES LAUNCHED FROM NOW ON MIGRATE WITH THE NEW FEE, EXISTING ONES KEEP THEIRS
+    const { curvePDA } = await createCurve();
+    const newCurve = await program.account.bondingCurve.fetch(curvePDA);
+    const existingCurve = await program.account.bondingCurve.fetch(bondingCurvePDA);
+    assert.strictEqual(newCurve.migrateFeeAmount.toNumber(), 1000);
+    assert.strictEqual(existingCurve.migrateFeeAmount.toNumber(), 500);
+  });
+
+  it("Buy prices are consistent for 0, 6 and 9 decimal mints", async () => {
+    //ENDS ON 6 DECIMALS SO LATER TESTS SEE THE ORIGINAL SETTINGS
+    for (const mintDecimals of [0, 9, 6]) {
+      await program.methods
+        .setParams(await settingsWith({ mintDecimals }))
+        .accounts({ authority: admin.publicKey, global: globalPDA })
+        .signers([admin])
+        .rpc();
+
+      const { mint } = await createCurve(
+        { name: "Decimals", symbol: "DEC" }
+      );
+
+      const user1CurveTokenAccount = await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        user1,
+        mint,
+        user1.publicKey
+      );
+
+      await program.methods
+        .buy(SOL_FOR_BUY, new anchor.BN(0))
+        .accounts(tradeAccounts(mint, user1.publicKey))
+        .signers([user1])
+        .rpc();
+
+      const userCurveTokenAccountInfo = await getAccount(
+        provider.connection,
+        user1CurveTokenAccount.address
+      );
+      //30 SOL x 1_073_000_000_000_000 BASE UNITS, 0.2 SOL IN: THE NEW TOKEN RESERVE
+      //3.219e25 / 30.2e9 ROUNDS UP TO 1_065_894_039_735_100, WHATEVER THE DECIMALS
+      assert.strictEqual(userCurveTokenAccountInfo.amount.toString(), "7105960264900");
+    }
+  });
+
+  it("Linear, exponential and stepped curves price through their own shape", async () => {
+    const globalState = await program.account.global.fetch(globalPDA);
+    const tokensBought = {};
+    const precision = new anchor.BN("1000000000000000000");
+
+    //MIRRORS THE ON-CHAIN TIER WALK OF A BUY SPENDING `solAmount`
+    const tiers = {
+      exponential: { count: 16, nextPrice: (price, start) => price.muln(11_487).divn(10_000) },
+      stepped: { count: 8, nextPrice: (price, start) => price.add(start) },
+    };
+    const tieredTokensForBuy = (curve, kind: string, solAmount: anchor.BN) => {
+      const { count, nextPrice } = tiers[kind];
+      const width = anchor.BN.max(curve.initialRealTokenReserves.divn(count), new anchor.BN(1));
+      const start = curve.initialVirtualSolReserves.mul(precision).div(curve.initialVirtualTokenReserves);
+      const sold = curve.initialRealTokenReserves.sub(curve.realTokenReserves);
+      let tier = Math.min(sold.div(width).toNumber(), count - 1);
+      let price = start;
+      for (let i = 0; i < tier; i++) {
+        price = nextPrice(price, start);
+      }
+
+      let budget = solAmount.mul(precision);
+      let position = sold;
+      while (tier < count - 1) {
+        const tierCost = width.muln(tier + 1).sub(position).mul(price);
+        if (tierCost.gt(budget)) {
+          break;
+        }
+        budget = budget.sub(tierCost);
+        position = width.muln(tier + 1);
+        tier += 1;
+        price = nextPrice(price, start);
+      }
+      return position.add(budget.div(price)).sub(sold);
+    };
+
+    //TRADER CROSSES TIERS AND SELLS BACK ON EVERY CURVE
+    const trader = anchor.web3.Keypair.generate();
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(trader.publicKey, 15 * 1_000_000_000),
+      "confirmed"
+    );
+    const CROSSING_BUY = new anchor.BN(3 * 1_000_000_000);
+
+    for (const curveKind of [{ linear: {} }, { exponential: {} }, { stepped: {} }]) {
+      const { mint, curvePDA } = await createCurve(
+        { name: "Shaped", symbol: "SHP", curveKind }
+      );
+
+      const curve = await program.account.bondingCurve.fetch(curvePDA);
+      assert.deepEqual(curve.curveKind, curveKind);
+
+      const user1CurveTokenAccount = await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        user1,
+        mint,
+        user1.publicKey
+      );
+
+      const quote = await program.methods
+        .quoteBuy(SOL_FOR_BUY)
+        .accounts({ global: globalPDA, mint, bondingCurve: curvePDA })
+        .view();
+
+      await program.methods
+        .buy(SOL_FOR_BUY, new anchor.BN(0))
+        .accounts(tradeAccounts(mint, user1.publicKey))
+        .signers([user1])
+        .rpc();
+
+      const userTokens = await getAccount(provider.connection, user1CurveTokenAccount.address);
+      assert.strictEqual(userTokens.amount.toString(), quote.tokenAmount.toString());
+      const kind = Object.keys(curveKind)[0];
+      tokensBought[kind] = quote.tokenAmount;
+
+      const traderTokenAccount = await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        trader,
+        mint,
+        trader.publicKey
+      );
+      const traderAccounts = tradeAccounts(mint, trader.publicKey);
+
+      //A BUY LARGE ENOUGH TO CROSS INTO LATER TIERS
+      const curveBeforeCrossing = await program.account.bondingCurve.fetch(curvePDA);
+      const crossingQuote = await program.methods
+        .quoteBuy(CROSSING_BUY)
+        .accounts({ global: globalPDA, mint, bondingCurve: curvePDA })
+        .view();
+      await program.methods
+        .buy(CROSSING_BUY, new anchor.BN(0))
+        .accounts(traderAccounts)
+        .signers([trader])
+        .rpc();
+
+      const crossedTokens = new anchor.BN(
+        (await getAccount(provider.connection, traderTokenAccount.address)).amount.toString()
+      );
+      assert.strictEqual(crossedTokens.toString(), crossingQuote.tokenAmount.toString());
+      if (tiers[kind]) {
+        assert.strictEqual(
+          crossedTokens.toString(),
+          tieredTokensForBuy(curveBeforeCrossing, kind, CROSSING_BUY).toString()
+        );
+        const width = curveBeforeCrossing.initialRealTokenReserves.divn(tiers[kind].count);
+        const curveAfterCrossing = await program.account.bondingCurve.fetch(curvePDA);
+        const sold = curveAfterCrossing.initialRealTokenReserves.sub(
+          curveAfterCrossing.realTokenReserves
+        );
+        assert.isTrue(sold.gt(width));
+      }
+      //LATER TOKENS COST MORE, THE SAME SOL BUYS FEWER OF THEM AS THE CURVE RISES
+      assert.isTrue(crossedTokens.lt(quote.tokenAmount.mul(CROSSING_BUY).div(SOL_FOR_BUY)));
+
+      //SELLING IT ALL BACK RETURNS THE CURVE TO WHERE IT WAS, UP TO ROUNDING IN ITS FAVOUR
+      await program.methods
+        .sell(crossedTokens, new anchor.BN(0))
+        .accounts(traderAccounts)
+        .signers([trader])
+        .rpc();
+      const curveAfterRoundTrip = await program.account.bondingCurve.fetch(curvePDA);
+      assert.strictEqual(
+        curveAfterRoundTrip.realTokenReserves.toString(),
+        curveBeforeCrossing.realTokenReserves.toString()
+      );
+      const roundingDust = curveAfterRoundTrip.realSolReserves.sub(
+        curveBeforeCrossing.realSolReserves
+      );
+      assert.isTrue(roundingDust.gten(0) && roundingDust.lten(2));
+    }
+
+    //TIERED CURVES START FLAT AT THE CONSTANT PRODUCT LAUNCH PRICE
+    const startPrice = globalState.initialVirtualSolReserves
+      .mul(precision)
+      .div(globalState.initialVirtualTokenReserves);
+    const firstTierTokens = SOL_FOR_BUY.mul(precision).div(startPrice);
+    assert.strictEqual(tokensBought["stepped"].toString(), firstTierTokens.toString());
+    assert.strictEqual(tokensBought["exponential"].toString(), firstTierTokens.toString());
+    //LINEAR PRICE STARTS RISING WITH THE FIRST TOKEN SOLD
+    assert.isTrue(tokensBought["linear"].lt(firstTierTokens));
+  });
+
+  it("Creator picks launch market cap and supply within global bounds", async () => {
+    const createWithLaunch = (launch) => createCurve({ name: "Launch", symbol: "LCH", ...launch });
+
+    //UNSET VALUES FALL BACK TO THE GLOBAL DEFAULTS
+    const { mint, curvePDA } = await createWithLaunch({
+      initialVirtualSolReserves: new anchor.BN(60 * 1_000_000_000),
+      initialVirtualTokenReserves: null,
+      initialRealTokenReserves: new anchor.BN(1000000000000000),
+      tokenTotalSupply: new anchor.BN(1500000000000000),
+    });
+
+    const curve = await program.account.bondingCurve.fetch(curvePDA);
+    assert.strictEqual(curve.virtualSolReserves.toString(), "60000000000");
+    assert.strictEqual(curve.virtualTokenReserves.toString(), "1073000000000000");
+    assert.strictEqual(curve.realTokenReserves.toString(), "1000000000000000");
+    assert.strictEqual(curve.tokenTotalSupply.toString(), "1500000000000000");
+    const mintInfo = await getMint(provider.connection, mint);
+    assert.strictEqual(mintInfo.supply.toString(), "1500000000000000");
+
+    try {
+      await createWithLaunch({
+        initialVirtualSolReserves: new anchor.BN(200 * 1_000_000_000),
+        initialVirtualTokenReserves: null,
+        initialRealTokenReserves: null,
+        tokenTotalSupply: null,
+      });
+      assert.fail("create should have failed");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("LaunchParamsOutOfBounds");
+    }
+
+    //WITHIN BOUNDS BUT THE CURVE INVENTORY EXCEEDS THE SUPPLY
+    try {
+      await createWithLaunch({
+        initialVirtualSolReserves: null,
+        initialVirtualTokenReserves: null,
+        initialRealTokenReserves: new anchor.BN(1000000000000000),
+        tokenTotalSupply: new anchor.BN(1000000000000000),
+      });
+      assert.fail("create should have failed");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("InvalidLaunchParams");
+    }
+  });
+
+  it("Curve graduates once the SOL threshold is raised", async () => {
+    const threshold = new anchor.BN(2 * 1_000_000_000);
+
+    await program.methods
+      .setParams(await settingsWith({ graduationSolThreshold: threshold }))
+      .accounts({ authority: admin.publicKey, global: globalPDA })
+      .signers([admin])
+      .rpc();
+
+    const { mint, curvePDA } = await createCurve(
+      { name: "Threshold", symbol: "THR" }
+    );
+
+    const whaleCurveTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      whale,
+      mint,
+      whale.publicKey
+    );
+
+    const whaleAccounts = tradeAccounts(mint, whale.publicKey);
+    const quoteAccounts = { global: globalPDA, mint, bondingCurve: curvePDA };
+
+    //THE CURVE KEEPS THE THRESHOLD IT WAS CREATED WITH
+    let curve = await program.account.bondingCurve.fetch(curvePDA);
+    assert.strictEqual(curve.graduationSolThreshold.toString(), threshold.toString());
+
+    const firstBuy = new anchor.BN(1 * 1_000_000_000);
+    const firstQuote = await program.methods.quoteBuy(firstBuy).accounts(quoteAccounts).view();
+    await program.methods
+      .buy(firstBuy, new anchor.BN(0))
+      .accounts(whaleAccounts)
+      .signers([whale])
+      .rpc();
+
+    //LOWERING THE GLOBAL THRESHOLD BELOW THE SOL ALREADY RAISED DOES NOT STRAND THE CURVE
+    await program.methods
+      .setParams(
+        await settingsWith({ graduationSolThreshold: new anchor.BN(0.5 * 1_000_000_000) })
+      )
+      .accounts({ authority: admin.publicKey, global: globalPDA })
+      .signers([admin])
+      .rpc();
+
+    //A BUY PAST THE THRESHOLD ONLY RAISES THE REMAINING SOL, BY SOL OR BY TOKENS
+    const remainingSol = threshold.sub(firstBuy);
+    const oversizedBuy = new anchor.BN(5 * 1_000_000_000);
+    const quote = await program.methods.quoteBuy(oversizedBuy).accounts(quoteAccounts).view();
+    assert.strictEqual(quote.solAmount.toString(), remainingSol.toString());
+    assert.strictEqual(quote.complete, true);
+    const exactTokensQuote = await program.methods
+      .quoteBuyExactTokens(curve.realTokenReserves)
+      .accounts(quoteAccounts)
+      .view();
+    assert.strictEqual(exactTokensQuote.solAmount.toString(), remainingSol.toString());
+    assert.strictEqual(exactTokensQuote.tokenAmount.toString(), quote.tokenAmount.toString());
+    assert.strictEqual(exactTokensQuote.complete, true);
+
+    await program.methods
+      .buy(oversizedBuy, new anchor.BN(0))
+      .accounts(whaleAccounts)
+      .signers([whale])
+      .rpc();
+
+    curve = await program.account.bondingCurve.fetch(curvePDA);
+    assert.strictEqual(curve.complete, true);
+    assert.strictEqual(curve.realSolReserves.toString(), threshold.toString());
+    assert.isTrue(curve.realTokenReserves.gtn(0));
+    const whaleTokens = await getAccount(provider.connection, whaleCurveTokenAccount.address);
+    assert.strictEqual(
+      whaleTokens.amount.toString(),
+      firstQuote.tokenAmount.add(quote.tokenAmount).toString()
+    );
+
+    //NOTHING IS LEFT TO BUY ONCE THE CURVE GRADUATED
+    try {
+      await program.methods
+        .buy(firstBuy, new anchor.BN(0))
+        .accounts(whaleAccounts)
+        .signers([whale])
+        .rpc();
+      assert.fail("buy should have failed");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("BondingCurveComplete");
+    }
+
+    //RESTORE INVENTORY BASED GRADUATION FOR LATER TESTS
+    await program.methods
+      .setParams(await settingsWith({ graduationSolThreshold: new anchor.BN(0) }))
+      .accounts({ authority: admin.publicKey, global: globalPDA })
+      .signers([admin])
+      .rpc();
+  });
+
+  it("Wallet buys are capped during the launch window", async () => {
+    const launchWindowSeconds = 10;
+
+    //ONE BUY OF SOL_FOR_BUY FITS UNDER THE CAP, TWO DO NOT
+    await program.methods
+      .setParams(
+        await settingsWith({
+          launchWindowSeconds: new anchor.BN(launchWindowSeconds),
+          launchWindowMaxTokens: new anchor.BN(10000000000000),
+        })
+      )
+      .accounts({ authority: admin.publicKey, global: globalPDA })
+      .signers([admin])
+      .rpc();
+
+    const { mint, curvePDA } = await createCurve(
+      { name: "Window", symbol: "WIN" }
+    );
+
+    const curve = await program.account.bondingCurve.fetch(curvePDA);
+    assert.isTrue(curve.createdAt.gtn(0));
+    assert.strictEqual(
+      curve.launchWindowEndsAt.toString(),
+      curve.createdAt.addn(launchWindowSeconds).toString()
+    );
+    assert.strictEqual(curve.launchWindowMaxTokens.toString(), "10000000000000");
+
+    //THE WINDOW IS SNAPSHOTTED AT CREATION, TURNING IT OFF GLOBALLY DOES NOT LIFT THIS CURVE'S CAP
+    await program.methods
+      .setParams(
+        await settingsWith({
+          launchWindowSeconds: new anchor.BN(0),
+          launchWindowMaxTokens: new anchor.BN(0),
+        })
+      )
+      .accounts({ authority: admin.publicKey, global: globalPDA })
+      .signers([admin])
+      .rpc();
+
+    const user1CurveTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user1,
+      mint,
+      user1.publicKey
+    );
+    const user1LaunchBuys = launchBuysPDA(curvePDA, user1.publicKey);
+    const launchBuysAccounts = {
+      user: user1.publicKey,
+      global: globalPDA,
+      mint,
+      bondingCurve: curvePDA,
+      launchBuys: user1LaunchBuys,
+    };
+    const buyAccounts = tradeAccounts(mint, user1.publicKey, { launchBuys: user1LaunchBuys });
+
+    //BUYS IN THE WINDOW ARE TALLIED, SO THEY NEED THE WALLET'S LAUNCH BUYS ACCOUNT
+    try {
+      await program.methods
+        .buy(SOL_FOR_BUY, new anchor.BN(0))
+        .accounts({ ...buyAccounts, launchBuys: null })
+        .signers([user1])
+        .rpc();
+      assert.fail("buy should have failed");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("LaunchBuysRequired");
+    }
+
+    await program.methods
+      .buy(SOL_FOR_BUY, new anchor.BN(0))
+      .accounts(buyAccounts)
+      .preInstructions([
+        await program.methods
+          .openLaunchBuys()
+          .accounts({ ...launchBuysAccounts, systemProgram: SystemProgram.programId })
+          .instruction(),
+      ])
+      .signers([user1])
+      .rpc();
+
+    const launchBuys = await program.account.launchBuys.fetch(user1LaunchBuys);
+    const userTokens = await getAccount(provider.connection, user1CurveTokenAccount.address);
+    assert.strictEqual(launchBuys.tokensBought.toString(), userTokens.amount.toString());
+
+    try {
+      await program.methods
+        .buy(SOL_FOR_BUY, new anchor.BN(0))
+        .accounts(buyAccounts)
+        .signers([user1])
+        .rpc();
+      assert.fail("buy should have failed");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("LaunchWindowCapExceeded");
+    }
+
+    //THE TALLY STAYS UNTIL THE WINDOW IS OVER
+    try {
+      await program.methods
+        .closeLaunchBuys()
+        .accounts(launchBuysAccounts)
+        .signers([user1])
+        .rpc();
+      assert.fail("close should have failed");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("LaunchWindowOpen");
+    }
+
+    //WAIT FOR THE CLUSTER CLOCK TO LEAVE THE WINDOW, BUYS ARE UNCAPPED AFTER IT
+    const windowEnd = curve.launchWindowEndsAt.toNumber();
+    while ((await provider.connection.getBlockTime(await provider.connection.getSlot())) <= windowEnd) {
+      await new Promise((resolve) => setTimeout(resolve, 500));
+    }
+
+    await program.methods
+      .buy(SOL_FOR_BUY, new anchor.BN(0))
+      .accounts({ ...buyAccounts, launchBuys: null })
+      .signers([user1])
+      .rpc();
+
+    const userTokensAfter = await getAccount(provider.connection, user1CurveTokenAccount.address);
+    assert.isTrue(
+      new anchor.BN(userTokensAfter.amount.toString()).gt(new anchor.BN(userTokens.amount.toString()))
+    );
+
+    //THE WALLET GETS THE TALLY'S RENT BACK ONCE THE WINDOW IS OVER
+    const launchBuysRent = await provider.connection.getBalance(user1LaunchBuys);
+    const userBalanceBeforeClose = await provider.connection.getBalance(user1.publicKey);
+    await program.methods
+      .closeLaunchBuys()
+      .accounts(launchBuysAccounts)
+      .signers([user1])
+      .rpc();
+    const userBalanceAfterClose = await provider.connection.getBalance(user1.publicKey);
+    assert.isNull(await provider.connection.getAccountInfo(user1LaunchBuys));
+    assert.strictEqual(userBalanceAfterClose - userBalanceBeforeClose, launchBuysRent);
+
+    //AND NO NEW TALLY CAN BE OPENED AFTER IT
+    try {
+      await program.methods
+        .openLaunchBuys()
+        .accounts({ ...launchBuysAccounts, systemProgram: SystemProgram.programId })
+        .signers([user1])
+        .rpc();
+      assert.fail("open should have failed");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("LaunchWindowClosed");
+    }
+  });
+
+  it("Migration authority is rotated with propose and accept", async () => {
+    await program.methods
+      .proposeAuthority({ migration: {} }, user1.publicKey)
+      .accounts({ authority: admin.publicKey, global: globalPDA })
+      .signers([admin])
+      .rpc();
+
+    try {
+      await program.methods
+        .acceptAuthority({ migration: {} })
+        .accounts({ newAuthority: creator1.publicKey, global: globalPDA })
+        .signers([creator1])
+        .rpc();
+      assert.fail("accept_authority should have failed");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("WrongAuthority");
+    }
+
+    await program.methods
+      .acceptAuthority({ migration: {} })
+      .accounts({ newAuthority: user1.publicKey, global: globalPDA })
+      .signers([user1])
+      .rpc();
+
+    let state = await program.account.global.fetch(globalPDA);
+    assert.strictEqual(state.migrationAuthority.toString(), user1.publicKey.toString());
+    assert.strictEqual(state.globalAuthority.toString(), admin.publicKey.toString());
+    assert.strictEqual(state.pendingMigrationAuthority.toString(), PublicKey.default.toString());
+
+    //ROTATE BACK TO ADMIN
+    await program.methods
+      .proposeAuthority({ migration: {} }, admin.publicKey)
+      .accounts({ authority: user1.publicKey, global: globalPDA })
+      .signers([user1])
+      .rpc();
+    await program.methods
+      .acceptAuthority({ migration: {} })
+      .accounts({ newAuthority: admin.publicKey, global: globalPDA })
+      .signers([admin])
+      .rpc();
+
+    state = await program.account.global.fetch(globalPDA);
+    assert.strictEqual(state.migrationAuthority.toString(), admin.publicKey.toString());
+  });
+
+  it("Global authority is rotated with propose and accept", async () => {
+    await program.methods
+      .proposeAuthority({ global: {} }, creator1.publicKey)
+      .accounts({ authority: admin.publicKey, global: globalPDA })
+      .signers([admin])
+      .rpc();
+    await program.methods
+      .acceptAuthority({ global: {} })
+      .accounts({ newAuthority: creator1.publicKey, global: globalPDA })
+      .signers([creator1])
+      .rpc();
+
+    let state = await program.account.global.fetch(globalPDA);
+    assert.strictEqual(state.globalAuthority.toString(), creator1.publicKey.toString());
+    assert.strictEqual(state.migrationAuthority.toString(), admin.publicKey.toString());
+    assert.strictEqual(state.pendingGlobalAuthority.toString(), PublicKey.default.toString());
+
+    //THE OLD AUTHORITY CAN NO LONGER CHANGE SETTINGS, THE NEW ONE CAN
+    const params = await settingsWith();
+    try {
+      await program.methods
+        .setParams(params)
+        .accounts({ authority: admin.publicKey, global: globalPDA })
+        .signers([admin])
+        .rpc();
+      assert.fail("set_params should have failed");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("WrongAuthority");
+    }
+    await program.methods
+      .setParams(params)
+      .accounts({ authority: creator1.publicKey, global: globalPDA })
+      .signers([creator1])
+      .rpc();
+
+    //ROTATE BACK TO ADMIN
+    await program.methods
+      .proposeAuthority({ global: {} }, admin.publicKey)
+      .accounts({ authority: creator1.publicKey, global: globalPDA })
+      .signers([creator1])
+      .rpc();
+    await program.methods
+      .acceptAuthority({ global: {} })
+      .accounts({ newAuthority: admin.publicKey, global: globalPDA })
+      .signers([admin])
+      .rpc();
+
+    state = await program.account.global.fetch(globalPDA);
+    assert.strictEqual(state.globalAuthority.toString(), admin.publicKey.toString());
+  });
+
+  it("Completed bonding curve is migrated to the AMM", async () => {
+    const whaleTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      whale,
+      tokenMint1,
+      whale.publicKey
+    );
+
+    const whaleBalanceBefore = await provider.connection.getBalance(whale.publicKey);
+    const bondingCurveBeforeCompletion = await program.account.bondingCurve.fetch(bondingCurvePDA);
+
+    //BUY OUT THE REMAINING REAL TOKEN RESERVES
+    await program.methods
+      .buy(new anchor.BN(90 * 1_000_000_000), new anchor.BN(0))
+      .accounts(tradeAccounts(tokenMint1, whale.publicKey))
+      .signers([whale])
+      .rpc();
+
+    const bondingCurveAtCompletion = await program.account.bondingCurve.fetch(bondingCurvePDA);
+    assert.strictEqual(bondingCurveAtCompletion.complete, true);
+    assert.strictEqual(bondingCurveAtCompletion.realTokenReserves.toNumber(), 0);
+
+    //ONLY THE COST OF THE REMAINING TOKENS PLUS FEE IS CHARGED, THE REST OF THE 90 SOL STAYS WITH THE WHALE
+    const lastBuyCost = bondingCurveAtCompletion.realSolReserves.sub(
+      bondingCurveBeforeCompletion.realSolReserves
+    );
+    const lastBuyFee = lastBuyCost.muln(50).addn(9_999).divn(10_000);
+    const whaleBalanceAfter = await provider.connection.getBalance(whale.publicKey);
+    assert.strictEqual(
+      whaleBalanceBefore - whaleBalanceAfter,
+      lastBuyCost.add(lastBuyFee).toNumber()
+    );
+
+    //ESCROW HOLDS EXACTLY THE REAL SOL RESERVES ON TOP OF ITS RENT EXEMPT MINIMUM
+    const escrowBalance = await provider.connection.getBalance(bondingCurveSolEscrowPDA);
+    const escrowRent = await provider.connection.getMinimumBalanceForRentExemption(0);
+    assert.strictEqual(
+      escrowBalance,
+      bondingCurveAtCompletion.realSolReserves.toNumber() + escrowRent
+    );
+
+    const [poolPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool"), tokenMint1.toBuffer()],
+      mockAmm.programId
+    );
+    const poolTokenAccount = getAssociatedTokenAddressSync(tokenMint1, poolPDA, true);
+    //THE CURVE MIGRATES ON THE TERMS IT WAS CREATED WITH, NOT THE RAISED GLOBAL FEE
+    const globalState = await program.account.global.fetch(globalPDA);
+    assert.strictEqual(bondingCurveAtCompletion.migrateFeeAmount.toNumber(), 500);
+    assert.strictEqual(globalState.migrateFeeAmount.toNumber(), 1000);
+
+    await program.methods
+      .migrate()
+      .accounts({
+        migrationAuthority: admin.publicKey,
+        global: globalPDA,
+        feeVault: feeVaultPDA,
+        mint: tokenMint1,
+        bondingCurve: bondingCurvePDA,
+        bondingCurveTokenAccount: bondingCurveTokenAccount.address,
+        bondingCurveSolEscrow: bondingCurveSolEscrowPDA,
+        pool: poolPDA,
+        poolTokenAccount: poolTokenAccount,
+        ammProgram: mockAmm.programId,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([admin])
+      .rpc();
+
+    const pool = await mockAmm.account.pool.fetch(poolPDA);
+    const poolTokenAccountInfo = await getAccount(provider.connection, poolTokenAccount);
+    const bondingCurveAfterMigration = await program.account.bondingCurve.fetch(bondingCurvePDA);
+
+    assert.strictEqual(
+      pool.solReserves.toString(),
+      bondingCurveAtCompletion.realSolReserves
+        .sub(bondingCurveAtCompletion.migrateFeeAmount)
+        .toString()
+    );
+    assert.strictEqual(
+      poolTokenAccountInfo.amount.toString(),
+      bondingCurveAtCompletion.migrationTokenAllocation.toString()
+    );
+    assert.strictEqual(bondingCurveAfterMigration.migrated, true);
+    assert.strictEqual(bondingCurveAfterMigration.realSolReserves.toNumber(), 0);
+  });
+
+  it("Global authority withdraws collected fees", async () => {
+    const feeVaultRent = await provider.connection.getMinimumBalanceForRentExemption(
+      (await provider.connection.getAccountInfo(feeVaultPDA)).data.length
+    );
+    const withdrawable = (await provider.connection.getBalance(feeVaultPDA)) - feeVaultRent;
+
+    try {
+      await program.methods
+        .withdrawFees(new anchor.BN(withdrawable))
+        .accounts({
+          authority: user1.publicKey,
+          global: globalPDA,
+          feeVault: feeVaultPDA,
+          destination: user1.publicKey,
+        })
+        .signers([user1])
+        .rpc();
+      assert.fail("withdraw_fees should have failed");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("WrongAuthority");
+    }
+
+    try {
+      await program.methods
+        .withdrawFees(new anchor.BN(withdrawable + 1))
+        .accounts({
+          authority: admin.publicKey,
+          global: globalPDA,
+          feeVault: feeVaultPDA,
+          destination: FEE_RECEIVER.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+      assert.fail("withdraw_fees should have failed");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("InsufficientVaultFees");
+    }
+
+    await program.methods
+      .withdrawFees(new anchor.BN(withdrawable))
+      .accounts({
+        authority: admin.publicKey,
+        global: globalPDA,
+        feeVault: feeVaultPDA,
+        destination: FEE_RECEIVER.publicKey,
+      })
+      .signers([admin])
+      .rpc();
+
+    const feeVault = await program.account.feeVault.fetch(feeVaultPDA);
+    assert.strictEqual(await provider.connection.getBalance(FEE_RECEIVER.publicKey), withdrawable);
+    assert.strictEqual(await provider.connection.getBalance(feeVaultPDA), feeVaultRent);
+    assert.strictEqual(feeVault.totalFeesWithdrawn.toNumber(), withdrawable);
+    assert.strictEqual(
+      feeVault.totalFeesCollected.toNumber(),
+      feeVault.totalFeesWithdrawn.toNumber()
+    );
+  });
+
+  it("Creator claims accrued fees", async () => {
+    const creatorVaultRent = await provider.connection.getMinimumBalanceForRentExemption(
+      (await provider.connection.getAccountInfo(creatorVaultPDA)).data.length
+    );
+    const claimable = (await provider.connection.getBalance(creatorVaultPDA)) - creatorVaultRent;
+    const creatorBalanceBefore = await provider.connection.getBalance(creator1.publicKey);
+
+    await program.methods
+      .claimCreatorFees()
+      .accounts({ creator: creator1.publicKey, creatorVault: creatorVaultPDA })
+      .signers([creator1])
+      .rpc();
+
+    const creatorVault = await program.account.creatorVault.fetch(creatorVaultPDA);
+    const creatorBalanceAfter = await provider.connection.getBalance(creator1.publicKey);
+    assert.strictEqual(creatorBalanceAfter - creatorBalanceBefore, claimable);
+    assert.strictEqual(creatorVault.totalFeesClaimed.toNumber(), claimable);
+    assert.strictEqual(
+      creatorVault.totalFeesAccrued.toNumber(),
+      creatorVault.totalFeesClaimed.toNumber()
+    );
+
+    //NOTHING LEFT TO CLAIM
+    try {
+      await program.methods
+        .claimCreatorFees()
+        .accounts({ creator: creator1.publicKey, creatorVault: creatorVaultPDA })
+        .signers([creator1])
+        .rpc();
+      assert.fail("claim_creator_fees should have failed");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("InsufficientVaultFees");
+    }
+  });
 });